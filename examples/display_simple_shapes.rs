@@ -3,9 +3,12 @@ use std::iter::once;
 
 use wgpu::{Color, CommandEncoder, RenderPipeline, TextureView};
 use wgpu_noboiler::app::{AppCreator, AppData};
+use winit::event::{ElementState, KeyboardInput, VirtualKeyCode, WindowEvent};
 
+use wgpu_shapes::shape::line::{LineCap, LineJoin};
+use wgpu_shapes::shape::path::PathBuilder;
 use wgpu_shapes::shape::shapes::BasicShape;
-use wgpu_shapes::shape_renderer::ShapeRenderer;
+use wgpu_shapes::shape_renderer::{RenderMode, ShapeRenderer};
 
 struct State {
     shape_renderer: Option<ShapeRenderer>,
@@ -17,6 +20,7 @@ fn main() {
     })
     .render(render)
     .init(init)
+    .window_event(event)
     .resizable(false)
     .title("Display simple shapes")
     .run();
@@ -63,7 +67,98 @@ fn render(
             .layer(5 - i as u16);
     }
 
-    shape_renderer.render(&mut encoder, &texture_view, &data.device);
+    shape_renderer
+        .rect()
+        .pos(-125.0, -25.0)
+        .scale(200.0, 200.0)
+        .color(1.0, 0.2, 0.2)
+        .alpha(0.5)
+        .stroke_width(10.0)
+        .stroke_color(0.5, 0.0, 0.0)
+        .layer(10);
+
+    shape_renderer
+        .oval()
+        .pos(200.0, -150.0)
+        .scale(150.0, 75.0)
+        .fill(false)
+        .stroke_width(5.0)
+        .stroke_color(1.0, 1.0, 0.0);
+
+    shape_renderer
+        .polyline(&[
+            (100.0, 150.0),
+            (150.0, 250.0),
+            (200.0, 150.0),
+            (250.0, 250.0),
+        ])
+        .thickness(10.0)
+        .color(1.0, 0.5, 0.0)
+        .join(LineJoin::Round)
+        .cap(LineCap::Round);
+
+    shape_renderer
+        .polygon(&[
+            (-350.0, 150.0),
+            (-250.0, 150.0),
+            (-250.0, 250.0),
+            (-300.0, 200.0),
+            (-350.0, 250.0),
+        ])
+        .hole(&[(-320.0, 160.0), (-280.0, 160.0), (-300.0, 180.0)])
+        .color(0.0, 0.8, 0.4)
+        .stroke_width(3.0)
+        .stroke_color(1.0, 1.0, 1.0);
+
+    let drop = PathBuilder::new()
+        .move_to(300.0, 250.0)
+        .quad_to((350.0, 175.0), (350.0, 150.0))
+        .arc_to((300.0, 150.0), -PI)
+        .quad_to((250.0, 175.0), (300.0, 250.0))
+        .close()
+        .build();
+
+    shape_renderer.fill_path(&drop).color(0.2, 0.6, 1.0);
+    shape_renderer
+        .stroke_path(&drop)
+        .color(1.0, 1.0, 1.0)
+        .thickness(4.0)
+        .layer(1);
+
+    shape_renderer
+        .rounded_rect()
+        .pos(-150.0, -250.0)
+        .scale(150.0, 80.0)
+        .corner_radii(30.0, 5.0, 30.0, 0.0)
+        .color(0.6, 0.2, 0.8)
+        .stroke_width(4.0)
+        .stroke_color(1.0, 1.0, 1.0);
+
+    shape_renderer
+        .arc()
+        .pos(300.0, -250.0)
+        .scale(100.0, 100.0)
+        .angles(PI / 2.0, PI * 2.0)
+        .inner_radius(0.7)
+        .color(0.2, 1.0, 0.2);
+
+    shape_renderer
+        .pie()
+        .pos(150.0, -250.0)
+        .scale(100.0, 80.0)
+        .angles(PI / 4.0, -PI / 4.0)
+        .color(1.0, 1.0, 0.0)
+        .stroke_width(2.0)
+        .stroke_color(0.0, 0.0, 0.0);
+
+    shape_renderer
+        .ring()
+        .pos(-350.0, -250.0)
+        .scale(80.0, 80.0)
+        .inner_radius(0.5)
+        .color(1.0, 0.4, 0.4);
+
+    shape_renderer.render(&mut encoder, &texture_view, &data.device, &data.queue);
 
     data.queue.submit(once(encoder.finish()));
 }
@@ -76,3 +171,25 @@ fn init(data: &AppData, state: &mut State, _: &mut Vec<RenderPipeline>) {
         .unwrap()
         .background_color(Color::BLACK);
 }
+
+/// space toggles between hard and smooth edges
+fn event(_app_data: &AppData, state: &mut State, window_event: &WindowEvent) {
+    if let WindowEvent::KeyboardInput {
+        input:
+            KeyboardInput {
+                state: ElementState::Pressed,
+                virtual_keycode: Some(VirtualKeyCode::Space),
+                ..
+            },
+        ..
+    } = window_event
+    {
+        let renderer = state.shape_renderer.as_mut().unwrap();
+
+        let render_mode = match renderer.render_mode() {
+            RenderMode::Tessellated => RenderMode::Sdf,
+            RenderMode::Sdf => RenderMode::Tessellated,
+        };
+        renderer.set_render_mode(render_mode);
+    }
+}
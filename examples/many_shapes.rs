@@ -1,5 +1,4 @@
 use std::iter::once;
-use rand::{Rng, thread_rng};
 
 use wgpu::{CommandEncoder, PowerPreference, PresentMode, RenderPipeline, TextureView};
 use wgpu_noboiler::app::{AppCreator, AppData};
@@ -53,19 +52,19 @@ fn render(
         }
     }
 
-    shape_renderer.render(&mut encoder, &texture_view, &data.device);
+    shape_renderer.render(&mut encoder, &texture_view, &data.device, &data.queue);
 
     data.queue.submit(once(encoder.finish()));
 }
 
 fn init(data: &AppData, state: &mut State, _: &mut Vec<RenderPipeline>) {
-
     state.shape_renderer = Some(ShapeRenderer::new(&data.device, &data.config));
     state
         .shape_renderer
         .as_mut()
         .unwrap()
-        .add_textures_from_bytes(&vec![include_bytes!("img.png")], &data.device, &data.queue);
+        .add_textures_from_bytes(&[include_bytes!("img.png")], &data.device, &data.queue)
+        .expect("the example image is valid");
 }
 
 fn resize(data: &AppData, state: &mut State, size: &PhysicalSize<u32>) {
@@ -4,22 +4,30 @@ use std::iter::once;
 use wgpu::{CommandEncoder, RenderPipeline, TextureView};
 use wgpu_noboiler::app::{AppCreator, AppData};
 use winit::dpi::PhysicalSize;
-use winit::event::{ElementState, MouseButton, WindowEvent};
+use winit::event::{
+    ElementState, KeyboardInput, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent,
+};
 
+use wgpu_shapes::camera::Camera2D;
+use wgpu_shapes::render::atlas::TextureId;
 use wgpu_shapes::shape::shapes::BasicShape;
 use wgpu_shapes::shape_renderer::ShapeRenderer;
 
 struct State {
     shape_renderer: Option<ShapeRenderer>,
+    camera: Camera2D,
     dragging: bool,
-    last_drag_pos: (f32, f32),
+    cursor_pos: (f32, f32),
+    textures: Vec<TextureId>,
 }
 
 fn main() {
     AppCreator::new(State {
         shape_renderer: None,
+        camera: Camera2D::new((800.0, 600.0)),
         dragging: false,
-        last_drag_pos: (0.0, 0.0),
+        cursor_pos: (0.0, 0.0),
+        textures: vec![],
     })
     .render(render)
     .init(init)
@@ -37,6 +45,7 @@ fn render(
     let shape_renderer = state.shape_renderer.as_mut().unwrap();
 
     shape_renderer.clear();
+    shape_renderer.set_camera(Some(state.camera));
 
     shape_renderer
         .oval()
@@ -69,74 +78,130 @@ fn render(
         .layer(100);
 
     shape_renderer
-        .image(0)
+        .image(state.textures[0])
+        .unwrap()
         .scale(200.0, 200.0)
         .layer(105)
         .rotation(PI / 4.0);
 
     shape_renderer
-        .image(1)
+        .image(state.textures[1])
+        .unwrap()
         .pos(300.0, 0.0)
         .scale(200.0, 200.0)
         .layer(105);
 
     shape_renderer
-        .image(0)
+        .image(state.textures[0])
+        .unwrap()
         .rotation(-PI / 2.0)
         .pos(-300.0, 0.0)
         .scale(200.0, 200.0)
         .layer(110);
 
-    shape_renderer.render(&mut encoder, &texture_view, &data.device);
+    shape_renderer
+        .rounded_image(state.textures[1])
+        .unwrap()
+        .pos(0.0, -300.0)
+        .scale(200.0, 150.0)
+        .corner_radius(40.0)
+        .layer(110);
+
+    // stays in the top left corner while the camera moves
+    let viewport = state.camera.viewport();
+    shape_renderer
+        .rect()
+        .screen_space(true)
+        .pos(-viewport.0 / 2.0 + 60.0, viewport.1 / 2.0 - 30.0)
+        .scale(100.0, 40.0)
+        .color(0.2, 0.2, 0.2)
+        .alpha(0.8)
+        .layer(200);
+
+    shape_renderer.render(&mut encoder, &texture_view, &data.device, &data.queue);
 
     data.queue.submit(once(encoder.finish()));
 }
 
 fn init(data: &AppData, state: &mut State, _: &mut Vec<RenderPipeline>) {
-    state.shape_renderer = Some(ShapeRenderer::new(&data.device, &data.config));
     state
+        .camera
+        .set_viewport((data.config.width as f32, data.config.height as f32));
+    state.shape_renderer = Some(ShapeRenderer::with_sample_count(
+        &data.device,
+        &data.config,
+        4,
+    ));
+    state.textures = state
         .shape_renderer
         .as_mut()
         .unwrap()
         .add_textures_from_bytes(
-            &vec![include_bytes!("img.png"), include_bytes!("img2.png")],
+            &[include_bytes!("img.png"), include_bytes!("img2.png")],
             &data.device,
             &data.queue,
-        );
+        )
+        .expect("the example images are valid");
 }
 
 fn resize(data: &AppData, state: &mut State, size: &PhysicalSize<u32>) {
+    state
+        .camera
+        .set_viewport((size.width as f32, size.height as f32));
     state
         .shape_renderer
         .as_mut()
         .unwrap()
-        .set_frame_size((size.width as f32, size.height as f32))
         .resize(&data.device, &data.config);
 }
 
 fn event(_app_data: &AppData, app_state: &mut State, window_event: &WindowEvent) {
     match window_event {
         WindowEvent::CursorMoved { position, .. } => {
-            if app_state.dragging {
-                let renderer = app_state.shape_renderer.as_mut().unwrap();
-
-                let mut offset = renderer.frame_offset();
+            let position = (position.x as f32, position.y as f32);
 
-                if app_state.last_drag_pos != (-1.0, -1.0) {
-                    offset.0 -= app_state.last_drag_pos.0 - position.x as f32;
-                    offset.1 += app_state.last_drag_pos.1 - position.y as f32;
-                }
+            // the world point which was under the cursor stays under it
+            if app_state.dragging {
+                let camera = &mut app_state.camera;
+                let from = camera.screen_to_world(app_state.cursor_pos);
+                let to = camera.screen_to_world(position);
 
-                renderer.set_frame_offset(offset);
-                app_state.last_drag_pos = (position.x as f32, position.y as f32);
+                camera.translate(from.0 - to.0, from.1 - to.1);
             }
+
+            app_state.cursor_pos = position;
         }
         WindowEvent::MouseInput { state, button, .. } => {
             if button != &MouseButton::Left {
                 return;
             }
             app_state.dragging = state == &ElementState::Pressed;
-            app_state.last_drag_pos = (-1.0, -1.0);
+        }
+        WindowEvent::MouseWheel { delta, .. } => {
+            let lines = match delta {
+                MouseScrollDelta::LineDelta(_, y) => *y,
+                MouseScrollDelta::PixelDelta(position) => position.y as f32 / 50.0,
+            };
+
+            app_state
+                .camera
+                .zoom_at(app_state.cursor_pos, 1.1_f32.powf(lines));
+        }
+        WindowEvent::KeyboardInput {
+            input:
+                KeyboardInput {
+                    state: ElementState::Pressed,
+                    virtual_keycode: Some(key),
+                    ..
+                },
+            ..
+        } => {
+            let camera = &mut app_state.camera;
+            match key {
+                VirtualKeyCode::Q => camera.set_rotation(camera.rotation() + PI / 16.0),
+                VirtualKeyCode::E => camera.set_rotation(camera.rotation() - PI / 16.0),
+                _ => camera,
+            };
         }
         _ => {}
     }
@@ -0,0 +1,79 @@
+use std::f32::consts::PI;
+
+use wgpu::{Color, TextureFormat};
+
+use wgpu_shapes::render::offscreen::OffscreenTarget;
+use wgpu_shapes::shape::shapes::BasicShape;
+use wgpu_shapes::shape_renderer::ShapeRenderer;
+
+/// renders without a window and saves the result as `offscreen.png`
+fn main() {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+
+    // machines without a gpu only have the software fallback adapter
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::default(),
+        force_fallback_adapter: false,
+        compatible_surface: None,
+    }))
+    .or_else(|| {
+        pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            force_fallback_adapter: true,
+            compatible_surface: None,
+        }))
+    })
+    .expect("no adapter found");
+
+    let (device, queue) = pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: None,
+            features: wgpu::Features::empty(),
+            limits: adapter.limits(),
+        },
+        None,
+    ))
+    .unwrap();
+
+    let size = (400, 300);
+    let format = TextureFormat::Rgba8UnormSrgb;
+
+    let mut shape_renderer = ShapeRenderer::with_format(&device, format, size, 1);
+    shape_renderer
+        .set_frame_size((size.0 as f32, size.1 as f32))
+        .background_color(Color::BLACK);
+
+    let texture = shape_renderer
+        .add_texture_from_bytes(include_bytes!("img.png"), &device, &queue)
+        .expect("the example image is valid");
+
+    shape_renderer
+        .oval()
+        .scale(250.0, 250.0)
+        .color(0.1, 0.5, 1.0)
+        .stroke_width(5.0)
+        .stroke_color(1.0, 1.0, 1.0);
+
+    shape_renderer
+        .rect()
+        .pos(-100.0, 50.0)
+        .scale(120.0, 80.0)
+        .rotation(PI / 8.0)
+        .color(1.0, 0.0, 0.0)
+        .alpha(0.5)
+        .layer(1);
+
+    shape_renderer
+        .image(texture)
+        .unwrap()
+        .pos(100.0, -50.0)
+        .scale(100.0, 100.0)
+        .layer(2);
+
+    let target = OffscreenTarget::new(&device, size, format);
+
+    shape_renderer
+        .render_to_image(&device, &queue, &target)
+        .save("offscreen.png")
+        .unwrap();
+}
@@ -0,0 +1,137 @@
+// Vertex shader
+
+// world -> clip space
+@group(0) @binding(0)
+var<uniform> view : mat3x3<f32>;
+
+// screen pixels -> clip space
+@group(0) @binding(2)
+var<uniform> screen : mat3x3<f32>;
+
+@group(0) @binding(1)
+var<uniform> pixelSize : f32;
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+};
+
+struct InstanceInput{
+    // rows of the matrix which places the scaled mesh in the frame
+    @location(1) transform_x: vec3<f32>,
+    @location(2) transform_y: vec3<f32>,
+    @location(3) scale: vec2<f32>,
+    @location(4) color: vec4<f32>,
+    @location(5) layer: u32,
+    @location(6) kind: u32,
+    @location(7) corner_radii: vec4<f32>,
+    @location(8) stroke_width: f32,
+    @location(9) stroke_color: vec4<f32>,
+    @location(10) screen_space: u32,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) local: vec2<f32>,
+    @location(2) half_size: vec2<f32>,
+    @location(3) @interpolate(flat) kind: u32,
+    @location(4) corner_radii: vec4<f32>,
+    @location(5) stroke_width: f32,
+    @location(6) stroke_color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.color = instance.color;
+    out.kind = instance.kind;
+    out.corner_radii = instance.corner_radii;
+    out.stroke_color = instance.stroke_color;
+
+    var halfSize = abs(instance.scale) / 2.0;
+    out.half_size = halfSize;
+    out.stroke_width = min(instance.stroke_width, min(halfSize.x, halfSize.y));
+
+    // the quad is a bit bigger than the shape so the smoothed edge fits in,
+    // the margin gets divided by the least the transform stretches any direction
+    var linear = vec4<f32>(instance.transform_x.xy, instance.transform_y.xy);
+    var squares = dot(linear, linear);
+    var determinant = linear.x * linear.w - linear.y * linear.z;
+    var stretch = sqrt(max((squares - sqrt(max(squares * squares - 4.0 * determinant * determinant, 0.0))) / 2.0, 0.0));
+    var margin = select(pixelSize, 1.0, instance.screen_space == 1u) * 2.0 / max(stretch, 0.0001);
+    var local = model.position * (halfSize + margin);
+    out.local = local;
+
+    var xLocation = local.x * sign(instance.scale.x);
+    var yLocation = local.y * sign(instance.scale.y);
+    var zLocation =  0.9 - (f32(instance.layer) / 75000.0);
+
+    var xPos = dot(instance.transform_x, vec3<f32>(xLocation, yLocation, 1.0));
+    var yPos = dot(instance.transform_y, vec3<f32>(xLocation, yLocation, 1.0));
+    var matrix = view;
+    if (instance.screen_space == 1u) {
+        matrix = screen;
+    }
+    var clip = matrix * vec3<f32>(xPos, yPos, 1.0);
+
+    out.clip_position = vec4<f32>(clip.xy, zLocation, 1.0);
+    return out;
+}
+
+// Fragment shader
+
+// radii are top left, top right, bottom right, bottom left
+fn box_distance(point: vec2<f32>, halfSize: vec2<f32>, radii: vec4<f32>) -> f32 {
+    var radius = radii.w;
+    if (point.x > 0.0 && point.y > 0.0) {
+        radius = radii.y;
+    } else if (point.x > 0.0) {
+        radius = radii.z;
+    } else if (point.y > 0.0) {
+        radius = radii.x;
+    }
+
+    var q = abs(point) - halfSize + radius;
+    return min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0))) - radius;
+}
+
+fn oval_distance(point: vec2<f32>, radii: vec2<f32>) -> f32 {
+    var r = max(radii, vec2<f32>(0.0001));
+    var k0 = length(point / r);
+    var k1 = length(point / (r * r));
+
+    if (k1 <= 0.0) {
+        return -min(r.x, r.y);
+    }
+
+    return k0 * (k0 - 1.0) / k1;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    var distance = box_distance(in.local, in.half_size, in.corner_radii);
+    if (in.kind == 1u) {
+        distance = oval_distance(in.local, in.half_size);
+    }
+
+    var smoothing = max(fwidth(distance), 0.0001);
+
+    var outer = clamp(0.5 - distance / smoothing, 0.0, 1.0);
+    var inner = 1.0;
+    if (in.stroke_width > 0.0) {
+        inner = clamp(0.5 - (distance + in.stroke_width) / smoothing, 0.0, 1.0);
+    }
+
+    // the stroke lies on the inside of the outline, the fill inside of the stroke
+    var alpha = mix(in.stroke_color.a, in.color.a, inner);
+    var premultiplied = mix(in.stroke_color.rgb * in.stroke_color.a, in.color.rgb * in.color.a, inner);
+
+    if (alpha <= 0.0) {
+        discard;
+    }
+
+    return vec4<f32>(premultiplied / alpha, alpha * outer);
+}
@@ -1,26 +1,30 @@
 // Vertex shader
 
+// world -> clip space
 @group(0) @binding(0)
-var<uniform> frameSize : vec2<f32>;
+var<uniform> view : mat3x3<f32>;
 
-@group(0) @binding(1)
-var<uniform> frameOffset : vec2<f32>;
+// screen pixels -> clip space
+@group(0) @binding(2)
+var<uniform> screen : mat3x3<f32>;
 
 struct VertexInput {
     @location(0) position: vec2<f32>,
 };
 
 struct InstanceInput{
-    @location(1) position: vec2<f32>,
-    @location(2) scale: vec2<f32>,
-    @location(3) rotation: f32,
-    @location(4) color: vec3<f32>,
+    // rows of the matrix which places the scaled mesh in the frame
+    @location(1) transform_x: vec3<f32>,
+    @location(2) transform_y: vec3<f32>,
+    @location(3) scale: vec2<f32>,
+    @location(4) color: vec4<f32>,
     @location(5) layer: u32,
+    @location(6) screen_space: u32,
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
-    @location(0) color: vec3<f32>,
+    @location(0) color: vec4<f32>,
 };
 
 @vertex
@@ -38,10 +42,15 @@ fn vs_main(
     var yLocation = model.position.y * yScale;
     var zLocation =  0.9 - (f32(instance.layer) / 75000.0);
 
-    var xPos = ((xLocation * cos(instance.rotation) - yLocation * sin(instance.rotation)) + instance.position.x + frameOffset.x) / frameSize.x * 2.0;
-    var yPos = ((xLocation * sin(instance.rotation) + yLocation * cos(instance.rotation)) + instance.position.y + frameOffset.y) / frameSize.y * 2.0;
+    var xPos = dot(instance.transform_x, vec3<f32>(xLocation, yLocation, 1.0));
+    var yPos = dot(instance.transform_y, vec3<f32>(xLocation, yLocation, 1.0));
+    var matrix = view;
+    if (instance.screen_space == 1u) {
+        matrix = screen;
+    }
+    var clip = matrix * vec3<f32>(xPos, yPos, 1.0);
 
-    out.clip_position = vec4<f32>(xPos,yPos,zLocation, 1.0);
+    out.clip_position = vec4<f32>(clip.xy, zLocation, 1.0);
     return out;
 }
 
@@ -49,5 +58,5 @@ fn vs_main(
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(in.color, 1.0);
+    return in.color;
 }
\ No newline at end of file
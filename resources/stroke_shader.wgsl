@@ -0,0 +1,79 @@
+// Vertex shader
+
+// world -> clip space
+@group(0) @binding(0)
+var<uniform> view : mat3x3<f32>;
+
+// screen pixels -> clip space
+@group(0) @binding(2)
+var<uniform> screen : mat3x3<f32>;
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) previous: vec2<f32>,
+    @location(2) next: vec2<f32>,
+    @location(3) inset: f32,
+};
+
+struct InstanceInput{
+    // rows of the matrix which places the scaled mesh in the frame
+    @location(4) transform_x: vec3<f32>,
+    @location(5) transform_y: vec3<f32>,
+    @location(6) scale: vec2<f32>,
+    @location(7) color: vec4<f32>,
+    @location(8) layer: u32,
+    @location(9) width: f32,
+    @location(10) screen_space: u32,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.color = instance.color;
+
+    var scale = instance.scale / 2.0;
+
+    var current = model.position * scale;
+    var previous = model.previous * scale;
+    var next = model.next * scale;
+
+    // the outline is counter clockwise -> the left side is the inside
+    var before = normalize(current - previous);
+    var after = normalize(next - current);
+    var beforeNormal = vec2<f32>(-before.y, before.x);
+    var afterNormal = vec2<f32>(-after.y, after.x);
+
+    // moves the point so its distance to both neighbouring edges is the stroke width
+    var miter = (beforeNormal + afterNormal) / (1.0 + dot(beforeNormal, afterNormal));
+    var width = min(instance.width, min(abs(scale.x), abs(scale.y)));
+
+    var xLocation = current.x + miter.x * width * model.inset;
+    var yLocation = current.y + miter.y * width * model.inset;
+    var zLocation =  0.9 - ((f32(instance.layer) + 0.5) / 75000.0);
+
+    var xPos = dot(instance.transform_x, vec3<f32>(xLocation, yLocation, 1.0));
+    var yPos = dot(instance.transform_y, vec3<f32>(xLocation, yLocation, 1.0));
+    var matrix = view;
+    if (instance.screen_space == 1u) {
+        matrix = screen;
+    }
+    var clip = matrix * vec3<f32>(xPos, yPos, 1.0);
+
+    out.clip_position = vec4<f32>(clip.xy, zLocation, 1.0);
+    return out;
+}
+
+// Fragment shader
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
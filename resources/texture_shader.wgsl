@@ -1,13 +1,15 @@
 // Vertex shader
 
+// world -> clip space
 @group(0) @binding(0)
-var<uniform> frameSize : vec2<f32>;
+var<uniform> view : mat3x3<f32>;
 
-@group(0) @binding(1)
-var<uniform> frameOffset : vec2<f32>;
+// screen pixels -> clip space
+@group(0) @binding(2)
+var<uniform> screen : mat3x3<f32>;
 
 @group(1) @binding(0)
-var t_diffuse: texture_2d<f32>;
+var t_diffuse: texture_2d_array<f32>;
 @group(1)@binding(1)
 var s_diffuse: sampler;
 
@@ -16,17 +18,24 @@ struct VertexInput {
 };
 
 struct InstanceInput{
-    @location(1) position: vec2<f32>,
-    @location(2) scale: vec2<f32>,
-    @location(3) rotation: f32,
+    // rows of the matrix which places the scaled mesh in the frame
+    @location(1) transform_x: vec3<f32>,
+    @location(2) transform_y: vec3<f32>,
+    @location(3) scale: vec2<f32>,
     @location(4) layer: u32,
     @location(5) texture_position: vec2<f32>,
     @location(6) texture_scale: vec2<f32>,
+    // page of the atlas
+    @location(7) texture_page: u32,
+    @location(8) alpha: f32,
+    @location(9) screen_space: u32,
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) tex_coords: vec2<f32>,
+    @location(1) alpha: f32,
+    @location(2) @interpolate(flat) page: u32,
 };
 
 @vertex
@@ -35,6 +44,8 @@ fn vs_main(
     instance: InstanceInput
 ) -> VertexOutput {
     var out: VertexOutput;
+    out.alpha = instance.alpha;
+    out.page = instance.texture_page;
 
     out.tex_coords.x = instance.texture_position.x + ((model.position.x + 1.0) / 2.0) * instance.texture_scale.x;
     out.tex_coords.y = instance.texture_position.y + (1.0 - (model.position.y + 1.0) / 2.0) * instance.texture_scale.y;
@@ -46,10 +57,15 @@ fn vs_main(
     var yLocation = model.position.y * yScale;
     var zLocation =  0.9 - (f32(instance.layer) / 75000.0);
 
-    var xPos = ((xLocation * cos(instance.rotation) - yLocation * sin(instance.rotation)) + instance.position.x + frameOffset.x) / frameSize.x * 2.0;
-    var yPos = ((xLocation * sin(instance.rotation) + yLocation * cos(instance.rotation)) + instance.position.y + frameOffset.y) / frameSize.y * 2.0;
+    var xPos = dot(instance.transform_x, vec3<f32>(xLocation, yLocation, 1.0));
+    var yPos = dot(instance.transform_y, vec3<f32>(xLocation, yLocation, 1.0));
+    var matrix = view;
+    if (instance.screen_space == 1u) {
+        matrix = screen;
+    }
+    var clip = matrix * vec3<f32>(xPos, yPos, 1.0);
 
-    out.clip_position = vec4<f32>(xPos,yPos,zLocation, 1.0);
+    out.clip_position = vec4<f32>(clip.xy, zLocation, 1.0);
     return out;
 }
 
@@ -57,5 +73,6 @@ fn vs_main(
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-   return textureSample(t_diffuse, s_diffuse, in.tex_coords);
+   var color = textureSample(t_diffuse, s_diffuse, in.tex_coords, i32(in.page));
+   return vec4<f32>(color.rgb, color.a * in.alpha);
 }
\ No newline at end of file
@@ -0,0 +1,185 @@
+/// world -> clip space, rows of a 3x3 matrix
+pub(crate) type ViewMatrix = [[f32; 3]; 3];
+
+/// view onto the world which can be moved, zoomed and rotated
+///
+/// used by the [ShapeRenderer](crate::shape_renderer::ShapeRenderer) instead of the frame size and offset
+/// with [set_camera](crate::shape_renderer::ShapeRenderer::set_camera)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Camera2D {
+    position: (f32, f32),
+    zoom: f32,
+    rotation: f32,
+    viewport: (f32, f32),
+}
+
+impl Camera2D {
+    /// creates a camera looking at (0,0) where one world unit is one pixel
+    ///
+    /// the viewport is the size of the surface in pixels
+    pub fn new(viewport: (f32, f32)) -> Self {
+        Camera2D {
+            position: (0.0, 0.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport,
+        }
+    }
+
+    /// sets the current [position](Camera2D::position)
+    pub fn set_position(&mut self, position: (f32, f32)) -> &mut Self {
+        self.position = position;
+        self
+    }
+
+    /// point of the world which is in the center of the viewport
+    pub fn position(&self) -> (f32, f32) {
+        self.position
+    }
+
+    /// sets the current [zoom](Camera2D::zoom)
+    pub fn set_zoom(&mut self, zoom: f32) -> &mut Self {
+        self.zoom = zoom;
+        self
+    }
+
+    /// size of one world unit in pixels
+    ///
+    /// 2.0 -> everything is twice as big
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// sets the current [rotation](Camera2D::rotation)
+    pub fn set_rotation(&mut self, rotation: f32) -> &mut Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// rotation of the camera in radians
+    ///
+    /// the world appears rotated the other way
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// sets the current [viewport](Camera2D::viewport), should be called on every window resize
+    pub fn set_viewport(&mut self, viewport: (f32, f32)) -> &mut Self {
+        self.viewport = viewport;
+        self
+    }
+
+    /// size of the surface in pixels
+    pub fn viewport(&self) -> (f32, f32) {
+        self.viewport
+    }
+
+    /// moves the camera by the given distance in world units
+    pub fn translate(&mut self, x: f32, y: f32) -> &mut Self {
+        self.position = (self.position.0 + x, self.position.1 + y);
+        self
+    }
+
+    /// multiplies the zoom by the factor while the world point under the screen point stays where it is
+    pub fn zoom_at(&mut self, screen_point: (f32, f32), factor: f32) -> &mut Self {
+        let before = self.screen_to_world(screen_point);
+        self.zoom *= factor;
+        let after = self.screen_to_world(screen_point);
+
+        self.translate(before.0 - after.0, before.1 - after.1)
+    }
+
+    /// point in the world -> point on the screen in pixels
+    ///
+    /// (0,0) -> top left of the viewport, y points down
+    pub fn world_to_screen(&self, world_point: (f32, f32)) -> (f32, f32) {
+        let (sin, cos) = self.rotation.sin_cos();
+        let x = world_point.0 - self.position.0;
+        let y = world_point.1 - self.position.1;
+
+        let view_x = (x * cos + y * sin) * self.zoom;
+        let view_y = (-x * sin + y * cos) * self.zoom;
+
+        (
+            self.viewport.0 / 2.0 + view_x,
+            self.viewport.1 / 2.0 - view_y,
+        )
+    }
+
+    /// point on the screen in pixels -> point in the world
+    ///
+    /// (0,0) -> top left of the viewport, y points down
+    pub fn screen_to_world(&self, screen_point: (f32, f32)) -> (f32, f32) {
+        let (sin, cos) = self.rotation.sin_cos();
+        let view_x = (screen_point.0 - self.viewport.0 / 2.0) / self.zoom;
+        let view_y = (self.viewport.1 / 2.0 - screen_point.1) / self.zoom;
+
+        (
+            view_x * cos - view_y * sin + self.position.0,
+            view_x * sin + view_y * cos + self.position.1,
+        )
+    }
+
+    /// size of a pixel in world units
+    pub(crate) fn pixel_size(&self, surface_size: (u32, u32)) -> f32 {
+        let width = self.viewport.0 / surface_size.0.max(1) as f32;
+        let height = self.viewport.1 / surface_size.1.max(1) as f32;
+
+        width.abs().min(height.abs()) / self.zoom.abs()
+    }
+
+    pub(crate) fn view_matrix(&self) -> ViewMatrix {
+        let (sin, cos) = self.rotation.sin_cos();
+        let x_scale = 2.0 * self.zoom / self.viewport.0;
+        let y_scale = 2.0 * self.zoom / self.viewport.1;
+        let (x, y) = self.position;
+
+        [
+            [x_scale * cos, x_scale * sin, -x_scale * (x * cos + y * sin)],
+            [
+                -y_scale * sin,
+                y_scale * cos,
+                -y_scale * (-x * sin + y * cos),
+            ],
+            [0.0, 0.0, 1.0],
+        ]
+    }
+}
+
+/// the view of a frame with the given size whose content gets moved by the offset
+pub(crate) fn frame_view_matrix(frame_size: (f32, f32), frame_offset: (f32, f32)) -> ViewMatrix {
+    [
+        [2.0 / frame_size.0, 0.0, frame_offset.0 * 2.0 / frame_size.0],
+        [0.0, 2.0 / frame_size.1, frame_offset.1 * 2.0 / frame_size.1],
+        [0.0, 0.0, 1.0],
+    ]
+}
+
+/// the view of screen space shapes -> one unit is one pixel, (0,0) -> center of the surface
+pub(crate) fn screen_view_matrix(surface_size: (u32, u32)) -> ViewMatrix {
+    frame_view_matrix(
+        (surface_size.0.max(1) as f32, surface_size.1.max(1) as f32),
+        (0.0, 0.0),
+    )
+}
+
+/// applies the matrix to a point
+pub(crate) fn transform_point(matrix: &ViewMatrix, point: [f32; 2]) -> [f32; 2] {
+    [
+        matrix[0][0] * point[0] + matrix[0][1] * point[1] + matrix[0][2],
+        matrix[1][0] * point[0] + matrix[1][1] * point[1] + matrix[1][2],
+    ]
+}
+
+/// layout of a mat3x3 uniform -> columns padded to four floats
+pub(crate) fn uniform_columns(matrix: &ViewMatrix) -> [f32; 12] {
+    let mut columns = [0.0; 12];
+
+    for column in 0..3 {
+        for row in 0..3 {
+            columns[column * 4 + row] = matrix[row][column];
+        }
+    }
+
+    columns
+}
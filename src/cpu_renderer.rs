@@ -0,0 +1,355 @@
+use image::RgbaImage;
+use wgpu::{Color, Limits};
+
+use crate::camera::{frame_view_matrix, Camera2D};
+use crate::error::Error;
+use crate::render::atlas::{TextureAtlas, TextureId};
+use crate::render::instance::{Instance, StrokeInstance, TextureInstance};
+use crate::render::rasterizer::{Pass, Rasterizer};
+use crate::render::stroke::stroke_mesh;
+use crate::shape::image::Image;
+use crate::shape::oval::{oval_indices, oval_outline, Oval};
+use crate::shape::rect::{Rect, RECT_INDICES, RECT_OUTLINE, RECT_VERTICES};
+use crate::shape::shapes::BasicShape;
+
+/// renders [Rect], [Oval] and [Image] shapes without a gpu
+///
+/// uses the same coordinates, layers and texture atlas as the [ShapeRenderer](crate::shape_renderer::ShapeRenderer)
+/// and gives the same result for every pixel whose center is not right on the edge of a shape.
+/// the output is deterministic which makes it usable for tests and on machines without a gpu
+pub struct CpuRenderer {
+    recs: Vec<Rect>,
+    ovals: Vec<Oval>,
+    images: Vec<Image>,
+
+    frame_size: (f32, f32),
+    frame_offset: (f32, f32),
+    camera: Option<Camera2D>,
+    background_color: Color,
+
+    atlas: TextureAtlas,
+}
+
+impl CpuRenderer {
+    /// creates a new [CpuRenderer], shapes can be added with [CpuRenderer::rect], [CpuRenderer::oval], ...
+    pub fn new() -> CpuRenderer {
+        CpuRenderer {
+            recs: vec![],
+            ovals: vec![],
+            images: vec![],
+            frame_size: (800.0, 600.0),
+            frame_offset: (0.0, 0.0),
+            camera: None,
+            background_color: Color::WHITE,
+            atlas: TextureAtlas::new(&Limits::default()),
+        }
+    }
+
+    /// renders the current shapes into an image of the given size
+    pub fn render(&self, size: (u32, u32)) -> RgbaImage {
+        let view = match &self.camera {
+            Some(camera) => camera.view_matrix(),
+            None => frame_view_matrix(self.frame_size, self.frame_offset),
+        };
+
+        let mut rasterizer = Rasterizer::new(size, view, self.background_color);
+
+        let rect_stroke = stroke_mesh(&RECT_OUTLINE);
+
+        // same order as the opaque render pass
+        for rect in &self.recs {
+            let instance = rect.to_instance();
+            if rect.data.fill && !instance.is_translucent() {
+                rasterizer.draw_shape(&RECT_VERTICES, &RECT_INDICES, &instance, Pass::Opaque);
+            }
+        }
+
+        for oval in &self.ovals {
+            let instance = oval.to_instance();
+            if oval.data.fill && !instance.is_translucent() {
+                let outline = oval_outline(oval.detail);
+                rasterizer.draw_shape(
+                    &outline,
+                    &oval_indices(outline.len()),
+                    &instance,
+                    Pass::Opaque,
+                );
+            }
+        }
+
+        for rect in &self.recs {
+            if let Some(instance) = rect.to_stroke_instance() {
+                if !instance.is_translucent() {
+                    rasterizer.draw_stroke(
+                        &rect_stroke.vertices,
+                        &rect_stroke.indices,
+                        &instance,
+                        Pass::Opaque,
+                    );
+                }
+            }
+        }
+
+        for oval in &self.ovals {
+            if let Some(instance) = oval.to_stroke_instance() {
+                if !instance.is_translucent() {
+                    let mesh = stroke_mesh(&oval_outline(oval.detail));
+                    rasterizer.draw_stroke(&mesh.vertices, &mesh.indices, &instance, Pass::Opaque);
+                }
+            }
+        }
+
+        for image in &self.images {
+            let Some(instance) = image.to_instance(&self.atlas) else {
+                continue;
+            };
+
+            if !instance.is_translucent() {
+                rasterizer.draw_texture(
+                    &RECT_VERTICES,
+                    &RECT_INDICES,
+                    &instance,
+                    self.atlas.pages(),
+                    Pass::Opaque,
+                );
+            }
+        }
+
+        // translucent shapes get blended sorted by their layer, strokes after the fills
+        let mut translucent: Vec<(u32, Translucent)> = vec![];
+
+        let basic_shapes = self.recs.iter().map(|rect| (&rect.data, None)).chain(
+            self.ovals
+                .iter()
+                .map(|oval| (&oval.data, Some(oval.detail))),
+        );
+
+        for (data, detail) in basic_shapes {
+            let instance = Instance::from(data);
+            if data.fill && instance.is_translucent() {
+                translucent.push((instance.layer * 2, Translucent::Fill(instance, detail)));
+            }
+
+            if let Some(instance) = Option::<StrokeInstance>::from(data) {
+                if instance.is_translucent() {
+                    translucent.push((
+                        instance.layer * 2 + 1,
+                        Translucent::Stroke(instance, detail),
+                    ));
+                }
+            }
+        }
+
+        translucent.extend(
+            self.images
+                .iter()
+                .filter_map(|image| image.to_instance(&self.atlas))
+                .filter(|instance| instance.is_translucent())
+                .map(|instance| (instance.layer * 2, Translucent::Image(instance))),
+        );
+
+        // stable -> shapes on the same layer keep their draw order
+        translucent.sort_by_key(|(order, _)| *order);
+
+        for (_, shape) in translucent {
+            match shape {
+                Translucent::Fill(instance, None) => rasterizer.draw_shape(
+                    &RECT_VERTICES,
+                    &RECT_INDICES,
+                    &instance,
+                    Pass::Translucent,
+                ),
+                Translucent::Fill(instance, Some(detail)) => {
+                    let outline = oval_outline(detail);
+                    rasterizer.draw_shape(
+                        &outline,
+                        &oval_indices(outline.len()),
+                        &instance,
+                        Pass::Translucent,
+                    );
+                }
+                Translucent::Stroke(instance, None) => rasterizer.draw_stroke(
+                    &rect_stroke.vertices,
+                    &rect_stroke.indices,
+                    &instance,
+                    Pass::Translucent,
+                ),
+                Translucent::Stroke(instance, Some(detail)) => {
+                    let mesh = stroke_mesh(&oval_outline(detail));
+                    rasterizer.draw_stroke(
+                        &mesh.vertices,
+                        &mesh.indices,
+                        &instance,
+                        Pass::Translucent,
+                    );
+                }
+                Translucent::Image(instance) => rasterizer.draw_texture(
+                    &RECT_VERTICES,
+                    &RECT_INDICES,
+                    &instance,
+                    self.atlas.pages(),
+                    Pass::Translucent,
+                ),
+            }
+        }
+
+        rasterizer.into_image()
+    }
+
+    /// clears the current shapes
+    pub fn clear(&mut self) {
+        self.recs.clear();
+        self.ovals.clear();
+        self.images.clear();
+    }
+
+    /// sets the current [frame_size](CpuRenderer::frame_size)
+    pub fn set_frame_size(&mut self, frame_size: (f32, f32)) -> &mut Self {
+        self.frame_size = frame_size;
+        self
+    }
+
+    /// frame_size.0 is the width in which shapes get displayed
+    /// frame_size.1 is the height in which shapes get displayed
+    ///
+    /// (0,0) -> center of the image
+    pub fn frame_size(&self) -> (f32, f32) {
+        self.frame_size
+    }
+
+    /// sets the current [frame_offset](CpuRenderer::frame_offset)
+    pub fn set_frame_offset(&mut self, frame_offset: (f32, f32)) -> &mut Self {
+        self.frame_offset = frame_offset;
+        self
+    }
+
+    /// frame_offset are values which get added to all shapes
+    pub fn frame_offset(&self) -> (f32, f32) {
+        self.frame_offset
+    }
+
+    /// sets the current [camera](CpuRenderer::camera)
+    pub fn set_camera(&mut self, camera: Option<Camera2D>) -> &mut Self {
+        self.camera = camera;
+        self
+    }
+
+    /// the [Camera2D] the shapes get viewed through
+    ///
+    /// None -> the [frame_size](CpuRenderer::frame_size) and [frame_offset](CpuRenderer::frame_offset) get used
+    pub fn camera(&self) -> Option<&Camera2D> {
+        self.camera.as_ref()
+    }
+
+    pub fn background_color(&mut self, background_color: Color) -> &mut Self {
+        self.background_color = background_color;
+        self
+    }
+
+    /// renders [Rect] and returns a Ref to it
+    pub fn rect(&mut self) -> &mut Rect {
+        self.recs.push(Rect::default());
+        self.recs.last_mut().unwrap()
+    }
+
+    /// renders [Oval] and returns a Ref to it
+    pub fn oval(&mut self) -> &mut Oval {
+        self.ovals.push(Oval::default());
+        self.ovals.last_mut().unwrap()
+    }
+
+    /// renders [Image] and returns a Ref to it
+    ///
+    /// fails if the texture was added to another renderer or got removed
+    pub fn image(&mut self, texture: TextureId) -> Result<&mut Image, Error> {
+        self.atlas.check(texture)?;
+
+        self.images.push(Image {
+            texture: Some(texture),
+            ..Default::default()
+        });
+        Ok(self.images.last_mut().unwrap())
+    }
+
+    /// sets the current [max_atlas_size](CpuRenderer::max_atlas_size), is used the next time textures get added
+    pub fn set_max_atlas_size(&mut self, max_atlas_size: u32) -> &mut Self {
+        self.atlas.set_max_size(max_atlas_size);
+        self
+    }
+
+    /// biggest width and height of a page of the texture atlas
+    ///
+    /// defaults to the max texture size wgpu supports everywhere
+    pub fn max_atlas_size(&self) -> u32 {
+        self.atlas.max_size()
+    }
+
+    /// sets the current [atlas_padding](CpuRenderer::atlas_padding)
+    ///
+    /// is used the next time textures get added, all textures get packed again then
+    pub fn set_atlas_padding(&mut self, atlas_padding: u32) -> &mut Self {
+        self.atlas.set_padding(atlas_padding);
+        self
+    }
+
+    /// pixels around every texture in the atlas which repeat its edge
+    ///
+    /// keeps neighbouring textures from bleeding into each other at the borders, defaults to 1
+    pub fn atlas_padding(&self) -> u32 {
+        self.atlas.padding()
+    }
+
+    /// pages of the texture atlas the images get sampled from
+    pub fn atlas_pages(&self) -> &[RgbaImage] {
+        self.atlas.pages()
+    }
+
+    /// removes the texture, its space in the atlas gets reused after [compact_textures](CpuRenderer::compact_textures)
+    /// or when added textures do not fit anymore
+    ///
+    /// false if the texture is from another renderer or was already removed
+    pub fn remove_texture(&mut self, texture: TextureId) -> bool {
+        self.atlas.remove(texture)
+    }
+
+    /// packs the remaining textures again so the space of removed ones is free, the atlas may get smaller
+    ///
+    /// every [TextureId] stays valid,
+    /// fails if a larger [atlas_padding](CpuRenderer::atlas_padding) does not fit anymore
+    pub fn compact_textures(&mut self) -> Result<(), Error> {
+        self.atlas.compact()?;
+        self.atlas.take_update();
+        Ok(())
+    }
+
+    /// adds a texture which can be drawn with [image](CpuRenderer::image)
+    ///
+    /// fails if the bytes are not an image
+    pub fn add_texture_from_bytes(&mut self, bytes: &[u8]) -> Result<TextureId, Error> {
+        let textures = self.add_textures_from_bytes(&[bytes])?;
+        Ok(textures[0])
+    }
+
+    /// adds multiple textures at once, if one of them fails nothing gets added
+    pub fn add_textures_from_bytes(&mut self, bytes: &[&[u8]]) -> Result<Vec<TextureId>, Error> {
+        let textures = self.atlas.add_from_bytes(bytes)?;
+
+        // the pages get sampled directly, there is nothing to upload
+        self.atlas.take_update();
+
+        Ok(textures)
+    }
+}
+
+impl Default for CpuRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// shape which gets blended, rects have no segment count
+enum Translucent {
+    Fill(Instance, Option<u32>),
+    Stroke(StrokeInstance, Option<u32>),
+    Image(TextureInstance),
+}
@@ -0,0 +1,69 @@
+//! errors of the renderers
+
+use std::fmt::{Display, Formatter};
+
+use image::ImageError;
+
+use crate::render::atlas::TextureId;
+
+/// why a texture could not be added or used
+#[derive(Debug)]
+pub enum Error {
+    /// the bytes are not an image in a supported format
+    Decode(ImageError),
+    /// the texture has no pixels and can not be placed in the atlas
+    EmptyTexture { width: u32, height: u32 },
+    /// the texture is bigger than a page of the atlas may get
+    TextureTooLarge {
+        width: u32,
+        height: u32,
+        max_size: u32,
+    },
+    /// the textures do not fit into the most pages the device supports
+    AtlasFull { pages: u32 },
+    /// the texture was added to another renderer or got removed
+    UnknownTexture(TextureId),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Decode(error) => write!(f, "could not decode the texture: {error}"),
+            Error::EmptyTexture { width, height } => {
+                write!(f, "the texture is empty ({width}x{height})")
+            }
+            Error::TextureTooLarge {
+                width,
+                height,
+                max_size,
+            } => write!(
+                f,
+                "the texture ({width}x{height}) is bigger than the atlas ({max_size}x{max_size})"
+            ),
+            Error::AtlasFull { pages } => {
+                write!(f, "the textures do not fit into {pages} atlas pages")
+            }
+            Error::UnknownTexture(texture) => {
+                write!(
+                    f,
+                    "{texture:?} was not added to this renderer or got removed"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ImageError> for Error {
+    fn from(error: ImageError) -> Self {
+        Error::Decode(error)
+    }
+}
@@ -0,0 +1,536 @@
+//! finds the shapes at a point or inside of an area on the cpu
+
+use image::RgbaImage;
+
+use crate::camera::{transform_point, ViewMatrix};
+use crate::math::{add, cross, dot, length, scale, sub};
+use crate::render::atlas::TextureAtlas;
+use crate::render::instance::Instance;
+use crate::render::mesh::Mesh;
+use crate::render::rasterizer::sample_nearest;
+use crate::render::stroke::{stroke_positions, StrokeVertex};
+use crate::retained::ShapeHandle;
+use crate::shape::arc::ArcGeometry;
+use crate::shape::image::Image;
+use crate::shape::line::Line;
+use crate::shape::path::PathShape;
+use crate::shape::polygon::Polygon;
+use crate::shape::rounded_rect::RoundedRect;
+use crate::shape::shapes::BasicShapeData;
+use crate::transform::{apply, invert, Affine};
+
+/// a shape of the [ShapeRenderer](crate::shape_renderer::ShapeRenderer) which got found by a hit test
+///
+/// the index is the position in the order the shapes of that kind got added since the last clear
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ShapeId {
+    Rect(usize),
+    Oval(usize),
+    Image(usize),
+    Line(usize),
+    Polygon(usize),
+    /// a filled or stroked path
+    Path(usize),
+    /// a rounded rect or a rounded image
+    RoundedRect(usize),
+    Arc(usize),
+    Pie(usize),
+    Ring(usize),
+    /// a retained shape
+    Retained(ShapeHandle),
+}
+
+/// corners of a triangle in the frame
+type Triangle = [[f32; 2]; 3];
+
+enum Outline {
+    Rect,
+    Oval,
+    /// triangles of the generated meshes of the fill and the stroke
+    Mesh {
+        fill: Vec<Triangle>,
+        stroke: Vec<Triangle>,
+    },
+}
+
+/// the part of a shape which contains a point
+enum Part {
+    Stroke,
+    /// with the point relative to the center of the shape, None for lines and paths
+    Fill(Option<[f32; 2]>),
+}
+
+/// everything of a shape a hit test needs
+pub(crate) struct HitShape {
+    id: ShapeId,
+    outline: Outline,
+    /// frame -> scaled shape, None if the shape is collapsed
+    to_local: Option<Affine>,
+    scale: [f32; 2],
+    screen_space: bool,
+    layer: u32,
+    fill: Option<Fill>,
+    /// width and if it is translucent
+    stroke: Option<(f32, bool)>,
+}
+
+struct Fill {
+    translucent: bool,
+    /// position, scale, page in the atlas and alpha of an image
+    texture: Option<([f32; 2], [f32; 2], u32, f32)>,
+}
+
+impl HitShape {
+    pub(crate) fn rect(id: ShapeId, data: &BasicShapeData) -> Self {
+        Self::basic(id, Outline::Rect, data)
+    }
+
+    pub(crate) fn oval(id: ShapeId, data: &BasicShapeData) -> Self {
+        Self::basic(id, Outline::Oval, data)
+    }
+
+    fn basic(id: ShapeId, outline: Outline, data: &BasicShapeData) -> Self {
+        HitShape {
+            id,
+            outline,
+            to_local: invert(&data.matrix()),
+            scale: [data.scale.0, data.scale.1],
+            screen_space: data.screen_space,
+            layer: data.layer as u32,
+            fill: data.fill.then_some(Fill {
+                translucent: data.color.3 < 1.0,
+                texture: None,
+            }),
+            stroke: (data.stroke_width > 0.0)
+                .then_some((data.stroke_width, data.stroke_color.3 < 1.0)),
+        }
+    }
+
+    pub(crate) fn polygon(id: ShapeId, polygon: &Polygon) -> Self {
+        Self::basic_mesh(
+            id,
+            &polygon.data,
+            &polygon.to_mesh(),
+            &polygon.to_stroke_mesh(),
+        )
+    }
+
+    pub(crate) fn arc(id: ShapeId, data: &BasicShapeData, geometry: &ArcGeometry) -> Self {
+        Self::basic_mesh(id, data, &geometry.to_mesh(), &geometry.to_stroke_mesh())
+    }
+
+    /// None if the texture of a rounded image got removed
+    pub(crate) fn rounded_rect(
+        id: ShapeId,
+        rounded_rect: &RoundedRect,
+        atlas: &TextureAtlas,
+    ) -> Option<Self> {
+        let mut shape = Self::basic_mesh(
+            id,
+            &rounded_rect.data,
+            &rounded_rect.to_mesh(),
+            &rounded_rect.to_stroke_mesh(),
+        );
+
+        if let (Some(texture), Some(fill)) = (rounded_rect.texture, &mut shape.fill) {
+            let (texture_pos, texture_scale, texture_page) = atlas.cords(texture)?;
+
+            fill.texture = atlas.in_pages(texture).then_some((
+                [texture_pos.0, texture_pos.1],
+                [texture_scale.0, texture_scale.1],
+                texture_page,
+                rounded_rect.data.color.3,
+            ));
+        }
+
+        Some(shape)
+    }
+
+    /// the fill and the stroke get hit on the triangles which get drawn
+    fn basic_mesh(
+        id: ShapeId,
+        data: &BasicShapeData,
+        fill: &Mesh,
+        stroke: &Mesh<StrokeVertex>,
+    ) -> Self {
+        let fill = if data.fill {
+            triangles(fill, &Instance::from(data))
+        } else {
+            vec![]
+        };
+
+        let stroke = Option::from(data)
+            .map(|instance| {
+                let positions = stroke_positions(&stroke.vertices, &instance);
+                corners(&positions, &stroke.indices)
+            })
+            .unwrap_or_default();
+
+        Self::basic(id, Outline::Mesh { fill, stroke }, data)
+    }
+
+    pub(crate) fn line(id: ShapeId, line: &Line) -> Self {
+        Self::mesh(id, &line.to_mesh(), &line.to_instance())
+    }
+
+    /// tolerance is the max distance in frame units between the curves and the generated mesh
+    pub(crate) fn path(id: ShapeId, path: &PathShape, tolerance: f32) -> Self {
+        Self::mesh(id, &path.to_mesh(tolerance), &path.to_instance())
+    }
+
+    /// lines and paths are only a fill without a stroke
+    fn mesh(id: ShapeId, mesh: &Mesh, instance: &Instance) -> Self {
+        HitShape {
+            id,
+            outline: Outline::Mesh {
+                fill: triangles(mesh, instance),
+                stroke: vec![],
+            },
+            to_local: None,
+            scale: instance.scale,
+            screen_space: instance.screen_space == 1,
+            layer: instance.layer,
+            fill: Some(Fill {
+                translucent: instance.color[3] < 1.0,
+                texture: None,
+            }),
+            stroke: None,
+        }
+    }
+
+    /// None if the texture got removed
+    ///
+    /// without its pixels on the pages the whole image gets hit
+    pub(crate) fn image(id: ShapeId, image: &Image, atlas: &TextureAtlas) -> Option<Self> {
+        let texture = image.texture?;
+        let (texture_pos, texture_scale, texture_page) = atlas.cords(texture)?;
+
+        Some(HitShape {
+            id,
+            outline: Outline::Rect,
+            to_local: invert(&image.matrix()),
+            scale: [image.scale.0, image.scale.1],
+            screen_space: image.screen_space,
+            layer: image.layer as u32,
+            fill: Some(Fill {
+                translucent: image.alpha < 1.0,
+                texture: atlas.in_pages(texture).then_some((
+                    [texture_pos.0, texture_pos.1],
+                    [texture_scale.0, texture_scale.1],
+                    texture_page,
+                    image.alpha,
+                )),
+            }),
+            stroke: None,
+        })
+    }
+
+    /// point in the frame -> point relative to the center of the shape without its rotation
+    fn local(&self, point: [f32; 2]) -> Option<[f32; 2]> {
+        Some(apply(self.to_local.as_ref()?, point))
+    }
+
+    fn half_size(&self) -> [f32; 2] {
+        scale(self.scale, 0.5).map(f32::abs)
+    }
+}
+
+/// tests [HitShapes](HitShape) which are given in draw order
+pub(crate) struct HitTester<'a> {
+    pub(crate) view: ViewMatrix,
+    pub(crate) surface_size: (u32, u32),
+    /// pages of the atlas, empty if their copies were dropped
+    pub(crate) atlas: &'a [RgbaImage],
+    /// minimal alpha of an image texel, None -> the whole image
+    pub(crate) image_alpha: Option<f32>,
+}
+
+/// what is drawn on top -> higher layer, strokes above fills,
+/// translucent above opaque, later translucent and earlier opaque shapes above the others
+type Order = (u32, bool, isize);
+
+fn order(level: u32, translucent: bool, index: usize) -> Order {
+    let index = index as isize;
+    (level, translucent, if translucent { index } else { -index })
+}
+
+impl HitTester<'_> {
+    /// the topmost shape which contains the point in the world
+    pub(crate) fn shape_at(&self, shapes: &[HitShape], point: (f32, f32)) -> Option<ShapeId> {
+        shapes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, shape)| {
+                let (level, translucent) =
+                    self.hit(shape, self.to_frame(shape, [point.0, point.1]))?;
+                Some((order(level, translucent, index), shape.id))
+            })
+            .max_by_key(|(order, _)| *order)
+            .map(|(_, id)| id)
+    }
+
+    /// every shape which intersects the area between the corners in the world, topmost first
+    pub(crate) fn shapes_in(
+        &self,
+        shapes: &[HitShape],
+        corner: (f32, f32),
+        opposite_corner: (f32, f32),
+    ) -> Vec<ShapeId> {
+        let area = [
+            [corner.0, corner.1],
+            [opposite_corner.0, corner.1],
+            [opposite_corner.0, opposite_corner.1],
+            [corner.0, opposite_corner.1],
+        ];
+
+        let mut found: Vec<_> = shapes
+            .iter()
+            .enumerate()
+            .filter(|(_, shape)| self.intersects(shape, area))
+            .map(|(index, shape)| {
+                let translucent = match &shape.fill {
+                    Some(fill) => fill.translucent,
+                    None => shape.stroke.is_some_and(|(_, translucent)| translucent),
+                };
+                (order(shape.layer * 2, translucent, index), shape.id)
+            })
+            .collect();
+
+        found.sort_by(|(a, _), (b, _)| b.cmp(a));
+        found.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// point in the world -> point in the space of the shape, screen space is in pixels
+    fn to_frame(&self, shape: &HitShape, point: [f32; 2]) -> [f32; 2] {
+        if !shape.screen_space {
+            return point;
+        }
+
+        let clip = transform_point(&self.view, point);
+        [
+            clip[0] * self.surface_size.0.max(1) as f32 / 2.0,
+            clip[1] * self.surface_size.1.max(1) as f32 / 2.0,
+        ]
+    }
+
+    /// level and if the hit part is translucent
+    fn hit(&self, shape: &HitShape, point: [f32; 2]) -> Option<(u32, bool)> {
+        let local = match self.part(shape, point)? {
+            Part::Stroke => {
+                let (_, translucent) = shape.stroke?;
+                return Some((shape.layer * 2 + 1, translucent));
+            }
+            Part::Fill(local) => local,
+        };
+
+        let fill = shape.fill.as_ref()?;
+
+        if let (Some((position, size, page, alpha)), Some(threshold)) =
+            (fill.texture, self.image_alpha)
+        {
+            let local = local?;
+
+            // same mapping as texture_shader.wgsl
+            let unit = [
+                local[0] * 2.0 / shape.scale[0],
+                local[1] * 2.0 / shape.scale[1],
+            ];
+            let tex_coords = [
+                position[0] + (unit[0] + 1.0) / 2.0 * size[0],
+                position[1] + (1.0 - (unit[1] + 1.0) / 2.0) * size[1],
+            ];
+
+            let page = self.atlas.get(page as usize)?;
+
+            if sample_nearest(page, tex_coords)[3] * alpha < threshold {
+                return None;
+            }
+        }
+
+        Some((shape.layer * 2, fill.translucent))
+    }
+
+    /// None if the point is outside of the shape
+    fn part(&self, shape: &HitShape, point: [f32; 2]) -> Option<Part> {
+        // the point and the half size without signs
+        let inside: fn([f32; 2], [f32; 2]) -> bool = match &shape.outline {
+            Outline::Rect => |[x, y], [half_x, half_y]| x <= half_x && y <= half_y,
+            Outline::Oval => {
+                |[x, y], [half_x, half_y]| (x / half_x).powi(2) + (y / half_y).powi(2) <= 1.0
+            }
+            Outline::Mesh { fill, stroke } => {
+                if stroke.iter().any(|triangle| contains(triangle, point)) {
+                    return Some(Part::Stroke);
+                }
+
+                return fill
+                    .iter()
+                    .any(|triangle| contains(triangle, point))
+                    .then(|| Part::Fill(shape.local(point)));
+            }
+        };
+
+        let [half_x, half_y] = shape.half_size();
+        if half_x == 0.0 || half_y == 0.0 {
+            return None;
+        }
+
+        let local = shape.local(point)?;
+        let distance = local.map(f32::abs);
+
+        if !inside(distance, [half_x, half_y]) {
+            return None;
+        }
+
+        // the stroke is along the inside of the outline
+        if let Some((width, _)) = shape.stroke {
+            let width = width.min(half_x.min(half_y));
+            let (inner_x, inner_y) = (half_x - width, half_y - width);
+
+            if inner_x <= 0.0 || inner_y <= 0.0 || !inside(distance, [inner_x, inner_y]) {
+                return Some(Part::Stroke);
+            }
+        }
+
+        Some(Part::Fill(Some(local)))
+    }
+
+    fn intersects(&self, shape: &HitShape, area: [[f32; 2]; 4]) -> bool {
+        let intersects_unit: fn(&[[f32; 2]]) -> bool = match &shape.outline {
+            Outline::Rect => intersects_unit_square,
+            Outline::Oval => intersects_unit_circle,
+            Outline::Mesh { fill, stroke } => {
+                let area = area.map(|point| self.to_frame(shape, point));
+
+                return fill
+                    .iter()
+                    .chain(stroke)
+                    .any(|triangle| intersects_convex(triangle, &area));
+            }
+        };
+
+        let [half_x, half_y] = shape.half_size();
+        if half_x == 0.0 || half_y == 0.0 {
+            return false;
+        }
+
+        let Some(to_local) = &shape.to_local else {
+            return false;
+        };
+
+        // the area in a space where the shape is the unit square or circle
+        let area = area.map(|point| {
+            let [x, y] = apply(to_local, self.to_frame(shape, point));
+            [x / half_x, y / half_y]
+        });
+
+        intersects_unit(&area)
+    }
+}
+
+/// shape_shader.wgsl, the triangles of the mesh in the frame
+fn triangles(mesh: &Mesh, instance: &Instance) -> Vec<Triangle> {
+    let half_scale = scale(instance.scale, 0.5);
+    let positions: Vec<_> = mesh
+        .vertices
+        .iter()
+        .map(|vertex| {
+            let local = [
+                vertex.position[0] * half_scale[0],
+                vertex.position[1] * half_scale[1],
+            ];
+            apply(&instance.transform, local)
+        })
+        .collect();
+
+    corners(&positions, &mesh.indices)
+}
+
+fn corners(positions: &[[f32; 2]], indices: &[i32]) -> Vec<Triangle> {
+    indices
+        .chunks_exact(3)
+        .map(|triangle| [0, 1, 2].map(|i| positions[triangle[i] as usize]))
+        .collect()
+}
+
+/// the triangle may be clockwise or counter clockwise, flat triangles contain nothing
+fn contains(triangle: &Triangle, point: [f32; 2]) -> bool {
+    let [a, b, c] = *triangle;
+    let area = cross(sub(b, a), sub(c, a));
+
+    if area == 0.0 {
+        return false;
+    }
+
+    [(a, b), (b, c), (c, a)]
+        .into_iter()
+        .all(|(start, end)| cross(sub(end, start), sub(point, start)) * area.signum() >= 0.0)
+}
+
+/// convex polygon against [-1,1]x[-1,1]
+fn intersects_unit_square(polygon: &[[f32; 2]]) -> bool {
+    intersects_convex(
+        polygon,
+        &[[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]],
+    )
+}
+
+/// separating axis test of two convex polygons
+fn intersects_convex(a: &[[f32; 2]], b: &[[f32; 2]]) -> bool {
+    let edges = |polygon: &[[f32; 2]]| {
+        (0..polygon.len())
+            .map(|i| {
+                let edge = sub(polygon[(i + 1) % polygon.len()], polygon[i]);
+                [-edge[1], edge[0]]
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let range = |polygon: &[[f32; 2]], axis: [f32; 2]| {
+        let projected = polygon.iter().map(|point| dot(*point, axis));
+        let min = projected.clone().fold(f32::INFINITY, f32::min);
+        let max = projected.fold(f32::NEG_INFINITY, f32::max);
+        (min, max)
+    };
+
+    edges(a).into_iter().chain(edges(b)).all(|axis| {
+        let (a_min, a_max) = range(a, axis);
+        let (b_min, b_max) = range(b, axis);
+
+        a_max >= b_min && a_min <= b_max
+    })
+}
+
+/// convex polygon against the circle with radius 1 around (0,0)
+fn intersects_unit_circle(polygon: &[[f32; 2]]) -> bool {
+    let edges: Vec<_> = (0..polygon.len())
+        .map(|i| (polygon[i], polygon[(i + 1) % polygon.len()]))
+        .collect();
+
+    let sides: Vec<_> = edges
+        .iter()
+        .map(|(start, end)| cross(sub(*end, *start), scale(*start, -1.0)))
+        .collect();
+
+    let center_inside =
+        sides.iter().all(|side| *side >= 0.0) || sides.iter().all(|side| *side <= 0.0);
+
+    center_inside
+        || edges
+            .iter()
+            .any(|(start, end)| distance_to_center(*start, *end) <= 1.0)
+}
+
+/// distance of (0,0) to the line segment
+fn distance_to_center(start: [f32; 2], end: [f32; 2]) -> f32 {
+    let edge = sub(end, start);
+    let length_squared = dot(edge, edge);
+
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (-dot(start, edge) / length_squared).clamp(0.0, 1.0)
+    };
+
+    length(add(start, scale(edge, t)))
+}
@@ -2,6 +2,13 @@
 //!
 //! best used with [wgpu_noboiler](https://crates.io/crates/wgpu-noboiler)
 
+pub mod camera;
+pub mod cpu_renderer;
+pub mod error;
+pub mod hit_test;
+pub(crate) mod math;
 pub mod render;
+pub mod retained;
 pub mod shape;
 pub mod shape_renderer;
+pub(crate) mod transform;
@@ -0,0 +1,42 @@
+//! small helpers for 2d vectors stored as `[x, y]`
+
+pub(crate) fn add(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+    [a[0] + b[0], a[1] + b[1]]
+}
+
+pub(crate) fn sub(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+pub(crate) fn scale(a: [f32; 2], factor: f32) -> [f32; 2] {
+    [a[0] * factor, a[1] * factor]
+}
+
+pub(crate) fn dot(a: [f32; 2], b: [f32; 2]) -> f32 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+pub(crate) fn cross(a: [f32; 2], b: [f32; 2]) -> f32 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+pub(crate) fn length(a: [f32; 2]) -> f32 {
+    dot(a, a).sqrt()
+}
+
+pub(crate) fn normalize(a: [f32; 2]) -> [f32; 2] {
+    scale(a, 1.0 / length(a))
+}
+
+/// rotated by 90 degrees counter clockwise
+pub(crate) fn perpendicular(a: [f32; 2]) -> [f32; 2] {
+    [-a[1], a[0]]
+}
+
+pub(crate) fn angle(a: [f32; 2]) -> f32 {
+    a[1].atan2(a[0])
+}
+
+pub(crate) fn polar(angle: f32, radius: f32) -> [f32; 2] {
+    [angle.cos() * radius, angle.sin() * radius]
+}
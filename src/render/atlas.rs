@@ -0,0 +1,594 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use image::{DynamicImage, GenericImage, GenericImageView, RgbaImage};
+use rectangle_pack::{
+    contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    TargetBin,
+};
+use wgpu::Limits;
+
+use crate::error::Error;
+
+/// position and size of a texture inside of the atlas and the page it is on
+///
+/// position and size are relative to the size of a page -> 0.0 to 1.0
+pub(crate) type TextureCords = ((f32, f32), (f32, f32), u32);
+
+/// reference to a texture added to a [ShapeRenderer](crate::shape_renderer::ShapeRenderer)
+/// or a [CpuRenderer](crate::cpu_renderer::CpuRenderer)
+///
+/// only the renderer which created it knows the texture
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextureId {
+    atlas: u32,
+    index: u32,
+}
+
+/// what has to be uploaded since the last [take_update](TextureAtlas::take_update)
+#[derive(Debug)]
+pub(crate) enum AtlasUpdate {
+    /// the textures got placed into free space, everything else stayed where it was
+    Placed(Vec<TextureId>),
+    /// every texture got placed again, the pages may have another size or count
+    ///
+    /// textures without a copy have to be moved inside of the uploaded atlas, from where they were
+    Repacked(Vec<(TextureId, Placement)>),
+}
+
+/// where a texture is in pixels
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Placement {
+    pub(crate) page: u32,
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+}
+
+/// part of a page in pixels
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Region {
+    pub(crate) page: u32,
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+/// rectangle which gets copied from the source to the same size at the destination
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct CopyRect {
+    pub(crate) from: (u32, u32),
+    pub(crate) to: (u32, u32),
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+struct Entry {
+    /// None once the copy got dropped after the upload
+    image: Option<RgbaImage>,
+    width: u32,
+    height: u32,
+    placement: Placement,
+}
+
+/// every atlas gets its own id so textures of other renderers are not found
+static NEXT_ATLAS_ID: AtomicU32 = AtomicU32::new(0);
+
+/// all textures packed into pages of the same size
+///
+/// the [ShapeRenderer](crate::shape_renderer::ShapeRenderer) uploads them into layers of a texture array,
+/// the [CpuRenderer](crate::cpu_renderer::CpuRenderer) samples the pages directly
+pub(crate) struct TextureAtlas {
+    id: u32,
+    /// removed textures leave a gap so their ids do not point to another texture
+    entries: Vec<Option<Entry>>,
+    /// width and height of every page
+    size: u32,
+    /// biggest size a page may grow to
+    max_size: u32,
+    /// biggest size the device supports
+    limit: u32,
+    max_pages: u32,
+    /// pixels around every texture filled with its edge
+    padding: u32,
+    /// padding the pages were packed with
+    packed_padding: u32,
+    /// false -> the pixels get dropped once they are uploaded and there are no pages
+    keep_copies: bool,
+    pages: Vec<RgbaImage>,
+    /// the space of the pages which is still free
+    bins: BTreeMap<u32, TargetBin>,
+    update: Option<AtlasUpdate>,
+}
+
+impl TextureAtlas {
+    /// size of a page before it has to grow
+    const START_SIZE: u32 = 512;
+    const DEFAULT_PADDING: u32 = 1;
+
+    pub(crate) fn new(limits: &Limits) -> Self {
+        TextureAtlas {
+            id: NEXT_ATLAS_ID.fetch_add(1, Ordering::Relaxed),
+            entries: vec![],
+            size: Self::START_SIZE.min(limits.max_texture_dimension_2d),
+            max_size: limits.max_texture_dimension_2d,
+            limit: limits.max_texture_dimension_2d,
+            max_pages: limits.max_texture_array_layers,
+            padding: Self::DEFAULT_PADDING,
+            packed_padding: Self::DEFAULT_PADDING,
+            keep_copies: true,
+            pages: vec![],
+            bins: BTreeMap::new(),
+            update: None,
+        }
+    }
+
+    /// caps the size of a page below the limit of the device
+    ///
+    /// gets used the next time the atlas gets packed
+    pub(crate) fn set_max_size(&mut self, max_size: u32) {
+        self.max_size = max_size.clamp(1, self.limit);
+    }
+
+    pub(crate) fn max_size(&self) -> u32 {
+        self.max_size
+    }
+
+    /// sets the space between the textures
+    ///
+    /// gets used the next time textures get added, everything gets packed again then
+    pub(crate) fn set_padding(&mut self, padding: u32) {
+        self.padding = padding;
+    }
+
+    pub(crate) fn padding(&self) -> u32 {
+        self.padding
+    }
+
+    /// false drops the pages, the copies of the textures get dropped by [uploaded](TextureAtlas::uploaded)
+    ///
+    /// true brings the pages back with the textures which still have a copy
+    pub(crate) fn set_keep_copies(&mut self, keep_copies: bool) {
+        self.keep_copies = keep_copies;
+
+        if keep_copies {
+            self.fill_pages();
+        } else {
+            self.pages = vec![];
+        }
+    }
+
+    pub(crate) fn keep_copies(&self) -> bool {
+        self.keep_copies
+    }
+
+    /// places the textures into the free space of the pages, everything gets packed again if they do not fit
+    ///
+    /// nothing gets added if one of the textures can not be placed
+    pub(crate) fn add(&mut self, textures: Vec<DynamicImage>) -> Result<Vec<TextureId>, Error> {
+        if let Some(texture) = textures
+            .iter()
+            .find(|texture| texture.width() == 0 || texture.height() == 0)
+        {
+            return Err(Error::EmptyTexture {
+                width: texture.width(),
+                height: texture.height(),
+            });
+        }
+
+        if let Some(texture) = textures
+            .iter()
+            .find(|texture| texture.width() > self.max_size || texture.height() > self.max_size)
+        {
+            return Err(Error::TextureTooLarge {
+                width: texture.width(),
+                height: texture.height(),
+                max_size: self.max_size,
+            });
+        }
+
+        let start = self.entries.len();
+        self.entries.extend(textures.into_iter().map(|texture| {
+            let image = texture.into_rgba8();
+
+            Some(Entry {
+                width: image.width(),
+                height: image.height(),
+                image: Some(image),
+                placement: Placement {
+                    page: 0,
+                    x: 0,
+                    y: 0,
+                },
+            })
+        }));
+
+        let ids: Vec<_> = (start..self.entries.len())
+            .map(|index| self.id(index))
+            .collect();
+
+        if self.padding == self.packed_padding && self.place(start) {
+            match &mut self.update {
+                Some(AtlasUpdate::Placed(placed)) => placed.extend(&ids),
+                Some(AtlasUpdate::Repacked(_)) => {}
+                None => self.update = Some(AtlasUpdate::Placed(ids.clone())),
+            }
+
+            return Ok(ids);
+        }
+
+        if let Err(error) = self.pack(self.size) {
+            self.entries.truncate(start);
+            return Err(error);
+        }
+
+        Ok(ids)
+    }
+
+    /// forgets the texture, its space gets reused the next time everything gets packed
+    ///
+    /// false if the texture is from another atlas or was already removed
+    pub(crate) fn remove(&mut self, texture: TextureId) -> bool {
+        if texture.atlas != self.id {
+            return false;
+        }
+
+        let Some(entry) = self.entries.get_mut(texture.index as usize) else {
+            return false;
+        };
+
+        if entry.take().is_none() {
+            return false;
+        }
+
+        match &mut self.update {
+            Some(AtlasUpdate::Placed(textures)) => textures.retain(|placed| *placed != texture),
+            Some(AtlasUpdate::Repacked(moved)) => moved.retain(|(moved, _)| *moved != texture),
+            None => {}
+        }
+
+        true
+    }
+
+    /// packs the remaining textures as tight as possible, the pages may get smaller or fewer
+    ///
+    /// nothing changes if they do not fit anymore because the padding got larger
+    pub(crate) fn compact(&mut self) -> Result<(), Error> {
+        self.pack(Self::START_SIZE.min(self.max_size))
+    }
+
+    /// what changed since the last call
+    pub(crate) fn take_update(&mut self) -> Option<AtlasUpdate> {
+        self.update.take()
+    }
+
+    /// every texture is on the gpu now, drops their copies if they are not kept
+    pub(crate) fn uploaded(&mut self) {
+        if self.keep_copies {
+            return;
+        }
+
+        for entry in self.entries.iter_mut().flatten() {
+            entry.image = None;
+        }
+    }
+
+    /// every texture which still has a copy
+    pub(crate) fn with_copies(&self) -> impl Iterator<Item = TextureId> + '_ {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.as_ref().is_some_and(|entry| entry.image.is_some()))
+            .map(|(index, _)| self.id(index))
+    }
+
+    /// the texture with its padding as far as it is inside of the page and where that goes
+    ///
+    /// None if the texture does not exist or has no copy anymore
+    pub(crate) fn padded_texture(&self, texture: TextureId) -> Option<(RgbaImage, Region)> {
+        let entry = self.entry(texture)?;
+        let image = entry.image.as_ref()?;
+        let region = self.region(entry);
+
+        let mut padded = RgbaImage::new(region.width, region.height);
+        for copy in self.extrusion(entry, (0, 0), entry.placement) {
+            let source = image.view(copy.from.0, copy.from.1, copy.width, copy.height);
+            padded
+                .copy_from(&*source, copy.to.0 - region.x, copy.to.1 - region.y)
+                .expect("the copies are inside of the region");
+        }
+
+        Some((padded, region))
+    }
+
+    /// where the texture is now and the copies which move it there from where it was
+    ///
+    /// the edges get copied into the padding
+    pub(crate) fn moves(
+        &self,
+        texture: TextureId,
+        from: Placement,
+    ) -> Option<(Placement, Vec<CopyRect>)> {
+        let entry = self.entry(texture)?;
+
+        Some((
+            entry.placement,
+            self.extrusion(entry, (from.x, from.y), entry.placement),
+        ))
+    }
+
+    /// decodes all images first so nothing gets added if one of them is broken
+    pub(crate) fn add_from_bytes(&mut self, bytes: &[&[u8]]) -> Result<Vec<TextureId>, Error> {
+        let textures = bytes
+            .iter()
+            .map(|bytes| image::load_from_memory(bytes))
+            .collect::<Result<_, _>>()?;
+
+        self.add(textures)
+    }
+
+    /// fails if the texture is from another renderer or got removed
+    pub(crate) fn check(&self, texture: TextureId) -> Result<(), Error> {
+        match self.entry(texture) {
+            Some(_) => Ok(()),
+            None => Err(Error::UnknownTexture(texture)),
+        }
+    }
+
+    /// where the texture is inside of the atlas without the padding
+    ///
+    /// None if it is from another atlas or got removed
+    pub(crate) fn cords(&self, texture: TextureId) -> Option<TextureCords> {
+        let entry = self.entry(texture)?;
+        let placement = entry.placement;
+        let size = self.size as f32;
+
+        Some((
+            (placement.x as f32 / size, placement.y as f32 / size),
+            (entry.width as f32 / size, entry.height as f32 / size),
+            placement.page,
+        ))
+    }
+
+    /// the pages with every texture, empty if the copies are not kept
+    pub(crate) fn pages(&self) -> &[RgbaImage] {
+        &self.pages
+    }
+
+    /// if the pixels of the texture are on the pages,
+    /// textures which lost their copy stay blank when the copies are kept again
+    pub(crate) fn in_pages(&self, texture: TextureId) -> bool {
+        self.keep_copies
+            && self
+                .entry(texture)
+                .is_some_and(|entry| entry.image.is_some())
+    }
+
+    pub(crate) fn page_count(&self) -> u32 {
+        self.bins.len() as u32
+    }
+
+    /// width and height of every page
+    pub(crate) fn size(&self) -> u32 {
+        self.size
+    }
+
+    fn id(&self, index: usize) -> TextureId {
+        TextureId {
+            atlas: self.id,
+            index: index as u32,
+        }
+    }
+
+    fn entry(&self, texture: TextureId) -> Option<&Entry> {
+        if texture.atlas != self.id {
+            return None;
+        }
+
+        self.entries.get(texture.index as usize)?.as_ref()
+    }
+
+    /// the part of the page which belongs to the texture, including the padding inside of the page
+    fn region(&self, entry: &Entry) -> Region {
+        let placement = entry.placement;
+        let x = placement.x.saturating_sub(self.packed_padding);
+        let y = placement.y.saturating_sub(self.packed_padding);
+
+        Region {
+            page: placement.page,
+            x,
+            y,
+            width: (placement.x + entry.width + self.packed_padding).min(self.size) - x,
+            height: (placement.y + entry.height + self.packed_padding).min(self.size) - y,
+        }
+    }
+
+    /// copies the texture from a position in the source to the placement and repeats its edges in the padding
+    fn extrusion(&self, entry: &Entry, from: (u32, u32), to: Placement) -> Vec<CopyRect> {
+        let columns = self.spans(from.0, to.x, entry.width);
+        let rows = self.spans(from.1, to.y, entry.height);
+
+        rows.iter()
+            .flat_map(|&(from_y, to_y, height)| {
+                columns.iter().map(move |&(from_x, to_x, width)| CopyRect {
+                    from: (from_x, from_y),
+                    to: (to_x, to_y),
+                    width,
+                    height,
+                })
+            })
+            .collect()
+    }
+
+    /// (from, to, length) along one axis, the texture itself and one pixel for each line of padding before and after it
+    fn spans(&self, from: u32, to: u32, length: u32) -> Vec<(u32, u32, u32)> {
+        let padding = self.packed_padding;
+
+        let before = (1..=padding.min(to)).map(|line| (from, to - line, 1));
+        let after = (0..padding)
+            .map(|line| (from + length - 1, to + length + line, 1))
+            .filter(|(_, to, _)| *to < self.size);
+
+        before
+            .chain(std::iter::once((from, to, length)))
+            .chain(after)
+            .collect()
+    }
+
+    /// places the textures from the index on into the free space of the pages, false if they do not fit
+    fn place(&mut self, start: usize) -> bool {
+        let mut rects_to_place: GroupedRectsToPlace<usize, usize> = GroupedRectsToPlace::new();
+
+        for (index, entry) in self.entries.iter().enumerate().skip(start) {
+            if let Some(entry) = entry {
+                rects_to_place.push_rect(index, None, self.padded_rect(entry));
+            }
+        }
+
+        // the bins get changed even if not everything fits
+        let mut bins = self.bins.clone();
+
+        let Ok(rectangle_placements) = pack_rects(
+            &rects_to_place,
+            &mut bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+        ) else {
+            return false;
+        };
+
+        self.bins = bins;
+
+        for (index, (page, location)) in rectangle_placements.packed_locations() {
+            self.put(*index, *page, location.x(), location.y());
+        }
+
+        true
+    }
+
+    /// places every texture
+    ///
+    /// the bins are bigger than the pages by the padding on every side,
+    /// so the padding of textures at the edge of a page lies outside of it.
+    /// the pages grow from the start size until all textures fit or they reach the max size, after that more pages get added.
+    /// nothing changes if the textures do not fit into the max count of pages
+    fn pack(&mut self, start_size: u32) -> Result<(), Error> {
+        let mut rects_to_place: GroupedRectsToPlace<usize, usize> = GroupedRectsToPlace::new();
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            if let Some(entry) = entry {
+                rects_to_place.push_rect(index, None, self.padded_rect(entry));
+            }
+        }
+
+        let mut size = start_size.min(self.max_size);
+        let mut page_count = 1;
+
+        let (rectangle_placements, mut bins) = loop {
+            let mut target_bins = BTreeMap::new();
+            let bin_size = size + 2 * self.padding;
+            for page in 0..page_count {
+                target_bins.insert(page, TargetBin::new(bin_size, bin_size, 1));
+            }
+
+            match pack_rects(
+                &rects_to_place,
+                &mut target_bins,
+                &volume_heuristic,
+                &contains_smallest_box,
+            ) {
+                Ok(rectangle_placements) => break (rectangle_placements, target_bins),
+                Err(_) if size < self.max_size => size = (size * 2).min(self.max_size),
+                Err(_) if page_count < self.max_pages => page_count += 1,
+                Err(_) => return Err(Error::AtlasFull { pages: page_count }),
+            }
+        };
+
+        // where the textures without a copy were before, an earlier move which was not uploaded yet wins
+        let mut moved: Vec<_> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let entry = entry.as_ref().filter(|entry| entry.image.is_none())?;
+                Some((self.id(index), entry.placement))
+            })
+            .collect();
+        if let Some(AtlasUpdate::Repacked(earlier)) = &self.update {
+            for (texture, from) in &mut moved {
+                if let Some((_, earlier)) = earlier.iter().find(|(moved, _)| moved == texture) {
+                    *from = *earlier;
+                }
+            }
+        }
+
+        self.size = size;
+        self.packed_padding = self.padding;
+
+        // the locations are not in the order of the textures
+        for (index, (page, location)) in rectangle_placements.packed_locations() {
+            let entry = self.entries[*index].as_mut().unwrap();
+            entry.placement = Placement {
+                page: *page,
+                x: location.x(),
+                y: location.y(),
+            };
+        }
+
+        // unused pages at the end do not have to be uploaded
+        let used = self
+            .entries
+            .iter()
+            .flatten()
+            .map(|entry| entry.placement.page + 1)
+            .max()
+            .unwrap_or(1);
+        bins.retain(|page, _| *page < used);
+        self.bins = bins;
+
+        self.fill_pages();
+        self.update = Some(AtlasUpdate::Repacked(moved));
+
+        Ok(())
+    }
+
+    /// draws every texture which has a copy onto new pages, if the pages are kept
+    fn fill_pages(&mut self) {
+        if !self.keep_copies {
+            return;
+        }
+
+        self.pages = vec![RgbaImage::new(self.size, self.size); self.page_count() as usize];
+        for index in 0..self.entries.len() {
+            if let Some(entry) = &self.entries[index] {
+                let placement = entry.placement;
+                self.put(index, placement.page, placement.x, placement.y);
+            }
+        }
+    }
+
+    fn padded_rect(&self, entry: &Entry) -> RectToInsert {
+        let padding = 2 * self.padding;
+        RectToInsert::new(entry.width + padding, entry.height + padding, 1)
+    }
+
+    /// places the texture and draws it with its padding onto the page if the pages are kept
+    ///
+    /// x and y are the position of the padded texture inside of the bin,
+    /// which is the position of the texture itself on the page
+    fn put(&mut self, index: usize, page: u32, x: u32, y: u32) {
+        let texture = self.id(index);
+        let entry = self.entries[index].as_mut().unwrap();
+        entry.placement = Placement { page, x, y };
+
+        if !self.keep_copies {
+            return;
+        }
+
+        if let Some((padded, region)) = self.padded_texture(texture) {
+            self.pages[page as usize]
+                .copy_from(&padded, region.x, region.y)
+                .expect("packed textures fit into the atlas");
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::ops::Range;
+
+use bytemuck::Pod;
+use wgpu::{Buffer, BufferSlice, BufferUsages, Device, Queue};
+
+/// gpu buffer which stays alive between frames and only gets recreated if the data does not fit anymore
+pub(crate) struct DynamicBuffer {
+    buffer: Buffer,
+    label: &'static str,
+    usage: BufferUsages,
+    len: u32,
+}
+
+impl DynamicBuffer {
+    /// smallest size in bytes so small frames do not recreate the buffer
+    const MIN_CAPACITY: u64 = 256;
+
+    pub(crate) fn vertex(device: &Device, label: &'static str) -> Self {
+        Self::new(device, label, BufferUsages::VERTEX)
+    }
+
+    pub(crate) fn indices(device: &Device, label: &'static str) -> Self {
+        Self::new(device, label, BufferUsages::INDEX)
+    }
+
+    fn new(device: &Device, label: &'static str, usage: BufferUsages) -> Self {
+        DynamicBuffer {
+            buffer: Self::create_buffer(device, label, usage, Self::MIN_CAPACITY),
+            label,
+            usage,
+            len: 0,
+        }
+    }
+
+    fn create_buffer(device: &Device, label: &str, usage: BufferUsages, size: u64) -> Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size,
+            usage: usage | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// replaces the content of the buffer
+    ///
+    /// grows to the next power of two if the data does not fit
+    pub(crate) fn write<T: Pod>(&mut self, device: &Device, queue: &Queue, data: &[T]) {
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+        let size = bytes.len() as u64;
+
+        if size > self.buffer.size() {
+            self.buffer = Self::create_buffer(
+                device,
+                self.label,
+                self.usage,
+                size.next_power_of_two().max(Self::MIN_CAPACITY),
+            );
+        }
+
+        if !bytes.is_empty() {
+            queue.write_buffer(&self.buffer, 0, bytes);
+        }
+
+        self.len = data.len() as u32;
+    }
+
+    /// like [write](DynamicBuffer::write) but only uploads the elements in the range if the data still fits
+    pub(crate) fn write_range<T: Pod>(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        data: &[T],
+        range: Range<usize>,
+    ) {
+        let size = std::mem::size_of_val(data) as u64;
+
+        if size > self.buffer.size() {
+            self.write(device, queue, data);
+            return;
+        }
+
+        if !range.is_empty() {
+            let offset = (range.start * std::mem::size_of::<T>()) as u64;
+            queue.write_buffer(&self.buffer, offset, bytemuck::cast_slice(&data[range]));
+        }
+
+        self.len = data.len() as u32;
+    }
+
+    pub(crate) fn slice(&self) -> BufferSlice<'_> {
+        self.buffer.slice(..)
+    }
+
+    /// count of the elements which got written last
+    pub(crate) fn size(&self) -> u32 {
+        self.len
+    }
+}
@@ -1,37 +1,118 @@
-use wgpu::VertexStepMode;
+use wgpu::{VertexBufferLayout, VertexStepMode};
 use wgpu_noboiler::vertex::Vertex;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Instance {
-    pub position: [f32; 2],
+    /// rows of the affine matrix which places the scaled mesh in the frame
+    pub transform: [[f32; 3]; 2],
     pub scale: [f32; 2],
-    pub rotation: f32,
-    //todo transparency
-    //problem: transparency only works in the same drawCall
-    pub color: [f32; 3],
+    pub color: [f32; 4],
     pub layer: u32,
+    /// 1 -> positioned in pixels on the screen instead of in the world
+    pub screen_space: u32,
 }
 
-impl Vertex<5> for Instance {
+impl Instance {
+    pub(crate) fn is_translucent(&self) -> bool {
+        self.color[3] < 1.0
+    }
+}
+
+impl Vertex<6> for Instance {
     const STEP_MODE: VertexStepMode = VertexStepMode::Instance;
 
-    const ATTRIBS: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![1 => Float32x2,2 => Float32x2,3 => Float32,4 => Float32x3, 5 => Uint32];
+    const ATTRIBS: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![1 => Float32x3, 2 => Float32x3, 3 => Float32x2, 4 => Float32x4, 5 => Uint32, 6 => Uint32];
 }
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct TextureInstance {
-    pub position: [f32; 2],
+    /// rows of the affine matrix which places the scaled mesh in the frame
+    pub transform: [[f32; 3]; 2],
     pub scale: [f32; 2],
-    pub rotation: f32,
     pub layer: u32,
     pub texture_position: [f32; 2],
     pub texture_scale: [f32; 2],
+    /// page of the atlas, a layer of the texture array
+    pub texture_page: u32,
+    pub alpha: f32,
+    pub screen_space: u32,
+}
+
+impl TextureInstance {
+    pub(crate) fn is_translucent(&self) -> bool {
+        self.alpha < 1.0
+    }
+}
+
+impl Vertex<9> for TextureInstance {
+    const STEP_MODE: VertexStepMode = VertexStepMode::Instance;
+
+    const ATTRIBS: [wgpu::VertexAttribute; 9] = wgpu::vertex_attr_array![1 => Float32x3, 2 => Float32x3, 3 => Float32x2, 4 => Uint32, 5 => Float32x2, 6 => Float32x2, 7 => Uint32, 8 => Float32, 9 => Uint32];
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct StrokeInstance {
+    /// rows of the affine matrix which places the scaled mesh in the frame
+    pub transform: [[f32; 3]; 2],
+    pub scale: [f32; 2],
+    pub color: [f32; 4],
+    pub layer: u32,
+    pub width: f32,
+    pub screen_space: u32,
+}
+
+impl StrokeInstance {
+    pub(crate) fn is_translucent(&self) -> bool {
+        self.color[3] < 1.0
+    }
 }
 
-impl Vertex<6> for TextureInstance {
+impl Vertex<7> for StrokeInstance {
     const STEP_MODE: VertexStepMode = VertexStepMode::Instance;
 
-    const ATTRIBS: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![1 => Float32x2,2 => Float32x2,3 => Float32, 4 => Uint32, 5 => Float32x2,6 => Float32x2];
+    const ATTRIBS: [wgpu::VertexAttribute; 7] = wgpu::vertex_attr_array![4 => Float32x3, 5 => Float32x3, 6 => Float32x2, 7 => Float32x4, 8 => Uint32, 9 => Float32, 10 => Uint32];
+}
+
+/// shape which gets described by a signed distance function
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub(crate) enum SdfKind {
+    /// rect with rounded corners
+    Box = 0,
+    Oval = 1,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SdfInstance {
+    /// rows of the affine matrix which places the scaled mesh in the frame
+    pub transform: [[f32; 3]; 2],
+    pub scale: [f32; 2],
+    pub color: [f32; 4],
+    pub layer: u32,
+    pub kind: u32,
+    /// top left, top right, bottom right, bottom left in frame units
+    pub corner_radii: [f32; 4],
+    pub stroke_width: f32,
+    pub stroke_color: [f32; 4],
+    pub screen_space: u32,
+}
+
+impl Vertex<10> for SdfInstance {
+    const STEP_MODE: VertexStepMode = VertexStepMode::Instance;
+
+    const ATTRIBS: [wgpu::VertexAttribute; 10] = wgpu::vertex_attr_array![1 => Float32x3, 2 => Float32x3, 3 => Float32x2, 4 => Float32x4, 5 => Uint32, 6 => Uint32, 7 => Float32x4, 8 => Float32, 9 => Float32x4, 10 => Uint32];
+}
+
+/// layout of an instance buffer which holds a copy of the instance for every vertex of its mesh
+///
+/// lets meshes with different instances get drawn together
+pub(crate) fn per_vertex(layout: VertexBufferLayout<'static>) -> VertexBufferLayout<'static> {
+    VertexBufferLayout {
+        step_mode: VertexStepMode::Vertex,
+        ..layout
+    }
 }
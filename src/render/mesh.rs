@@ -0,0 +1,70 @@
+use crate::render::vertex::Vertex;
+
+/// geometry which got generated on the cpu
+pub(crate) struct Mesh<V = Vertex> {
+    pub(crate) vertices: Vec<V>,
+    pub(crate) indices: Vec<i32>,
+}
+
+impl<V> Default for Mesh<V> {
+    fn default() -> Self {
+        Mesh {
+            vertices: vec![],
+            indices: vec![],
+        }
+    }
+}
+
+impl<V> Mesh<V> {
+    /// adds all vertices and indices of the other mesh
+    pub(crate) fn append(&mut self, other: Mesh<V>) {
+        let offset = self.vertices.len() as i32;
+
+        self.vertices.extend(other.vertices);
+        self.indices
+            .extend(other.indices.into_iter().map(|index| index + offset));
+    }
+}
+
+impl Mesh {
+    /// adds a vertex and returns its index
+    pub(crate) fn add_vertex(&mut self, position: [f32; 2]) -> i32 {
+        self.vertices.push(Vertex { position });
+        self.vertices.len() as i32 - 1
+    }
+
+    /// adds a triangle between already added vertices
+    ///
+    /// the order gets fixed to be counter clockwise
+    pub(crate) fn add_triangle(&mut self, a: i32, b: i32, c: i32) {
+        let [ax, ay] = self.vertices[a as usize].position;
+        let [bx, by] = self.vertices[b as usize].position;
+        let [cx, cy] = self.vertices[c as usize].position;
+
+        if (bx - ax) * (cy - ay) - (by - ay) * (cx - ax) >= 0.0 {
+            self.indices.extend([a, b, c]);
+        } else {
+            self.indices.extend([a, c, b]);
+        }
+    }
+
+    /// adds a triangle with its own vertices
+    pub(crate) fn add_triangle_points(&mut self, a: [f32; 2], b: [f32; 2], c: [f32; 2]) {
+        let a = self.add_vertex(a);
+        let b = self.add_vertex(b);
+        let c = self.add_vertex(c);
+
+        self.add_triangle(a, b, c);
+    }
+
+    /// adds a quad with its own vertices, the points have to be in order around the quad
+    pub(crate) fn add_quad_points(&mut self, a: [f32; 2], b: [f32; 2], c: [f32; 2], d: [f32; 2]) {
+        let a = self.add_vertex(a);
+        let b = self.add_vertex(b);
+        let c = self.add_vertex(c);
+        let d = self.add_vertex(d);
+
+        self.add_triangle(a, b, c);
+        self.add_triangle(a, c, d);
+    }
+}
@@ -1,3 +1,12 @@
+pub mod atlas;
 pub mod depth_buffer;
+pub(crate) mod dynamic_buffer;
 pub mod instance;
+pub(crate) mod mesh;
+pub mod offscreen;
+pub(crate) mod rasterizer;
+pub(crate) mod render_pipeline;
+pub(crate) mod retained_buffer;
+pub mod stroke;
+pub(crate) mod triangulation;
 pub mod vertex;
@@ -0,0 +1,151 @@
+use std::sync::mpsc;
+
+use image::RgbaImage;
+use wgpu::{Device, Queue, TextureFormat, TextureView};
+
+/// texture which can be rendered into without a window and read back into an [RgbaImage]
+///
+/// used with [ShapeRenderer::render_to_image](crate::shape_renderer::ShapeRenderer::render_to_image)
+pub struct OffscreenTarget {
+    texture: wgpu::Texture,
+    view: TextureView,
+    size: (u32, u32),
+    format: TextureFormat,
+}
+
+impl OffscreenTarget {
+    /// creates a target with the given size and format
+    ///
+    /// the format has to be a 8 bit rgba or bgra format
+    pub fn new(device: &Device, size: (u32, u32), format: TextureFormat) -> Self {
+        assert!(
+            Self::is_supported(format),
+            "offscreen targets need a 8 bit rgba or bgra format but got {:?}",
+            format
+        );
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("offscreen_texture"),
+            size: wgpu::Extent3d {
+                width: size.0,
+                height: size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        OffscreenTarget {
+            texture,
+            view,
+            size,
+            format,
+        }
+    }
+
+    fn is_supported(format: TextureFormat) -> bool {
+        matches!(
+            format,
+            TextureFormat::Rgba8Unorm
+                | TextureFormat::Rgba8UnormSrgb
+                | TextureFormat::Bgra8Unorm
+                | TextureFormat::Bgra8UnormSrgb
+        )
+    }
+
+    fn is_bgra(&self) -> bool {
+        matches!(
+            self.format,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb
+        )
+    }
+
+    pub fn view(&self) -> &TextureView {
+        &self.view
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    /// copies the content of the texture into an [RgbaImage]
+    ///
+    /// blocks until the gpu is done with all submitted work
+    pub fn read_image(&self, device: &Device, queue: &Queue) -> RgbaImage {
+        let (width, height) = self.size;
+
+        // every row of the copy has to be aligned to 256 bytes
+        let unpadded_bytes_per_row = width * 4;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("offscreen_readback_buffer"),
+            size: (padded_bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("offscreen_readback_encoder"),
+        });
+
+        encoder.copy_texture_to_buffer(
+            self.texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(padded_bytes_per_row),
+                    rows_per_image: std::num::NonZeroU32::new(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        queue.submit(Some(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            sender.send(result).ok();
+        });
+        device.poll(wgpu::Maintain::Wait);
+
+        receiver
+            .recv()
+            .expect("readback buffer got dropped")
+            .expect("could not map readback buffer");
+
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * height) as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+        buffer.unmap();
+
+        if self.is_bgra() {
+            for pixel in pixels.chunks_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        RgbaImage::from_raw(width, height, pixels).expect("readback has the size of the image")
+    }
+}
@@ -0,0 +1,293 @@
+use image::{Rgba, RgbaImage};
+use wgpu::Color;
+
+use crate::camera::{screen_view_matrix, transform_point, ViewMatrix};
+use crate::math::{cross, scale, sub};
+use crate::render::instance::{Instance, StrokeInstance, TextureInstance};
+use crate::render::stroke::{stroke_positions, StrokeVertex};
+use crate::transform::apply;
+
+/// how a triangle gets tested against and written into the depth buffer
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Pass {
+    /// replaces the color and writes the depth
+    Opaque,
+    /// blends the color and only gets tested against the depth
+    Translucent,
+}
+
+/// triangle rasterizer which does the same as the shaders of the [ShapeRenderer](crate::shape_renderer::ShapeRenderer)
+///
+/// renders into a Rgba8Unorm image, a pixel is covered if its center is inside of a triangle
+pub(crate) struct Rasterizer {
+    image: RgbaImage,
+    depth: Vec<f32>,
+    view: ViewMatrix,
+    screen: ViewMatrix,
+}
+
+impl Rasterizer {
+    pub(crate) fn new(size: (u32, u32), view: ViewMatrix, background_color: Color) -> Self {
+        let background = Rgba(
+            [
+                background_color.r,
+                background_color.g,
+                background_color.b,
+                background_color.a,
+            ]
+            .map(|channel| to_unorm(channel as f32)),
+        );
+
+        Rasterizer {
+            image: RgbaImage::from_pixel(size.0, size.1, background),
+            depth: vec![1.0; (size.0 * size.1) as usize],
+            view,
+            screen: screen_view_matrix(size),
+        }
+    }
+
+    pub(crate) fn into_image(self) -> RgbaImage {
+        self.image
+    }
+
+    /// point in the frame or on the screen -> position in normalized device coordinates
+    fn to_clip(&self, point: [f32; 2], screen_space: u32) -> [f32; 2] {
+        let matrix = if screen_space == 1 {
+            &self.screen
+        } else {
+            &self.view
+        };
+
+        transform_point(matrix, point)
+    }
+
+    /// shape_shader.wgsl
+    pub(crate) fn draw_shape(
+        &mut self,
+        vertices: &[[f32; 2]],
+        indices: &[i32],
+        instance: &Instance,
+        pass: Pass,
+    ) {
+        let half_scale = scale(instance.scale, 0.5);
+        let positions: Vec<_> = vertices
+            .iter()
+            .map(|vertex| {
+                let local = [vertex[0] * half_scale[0], vertex[1] * half_scale[1]];
+                self.to_clip(apply(&instance.transform, local), instance.screen_space)
+            })
+            .collect();
+
+        let depth = 0.9 - (instance.layer as f32 / 75000.0);
+        let color = instance.color;
+
+        for triangle in indices.chunks_exact(3) {
+            let points = [0, 1, 2].map(|i| (positions[triangle[i] as usize], [0.0; 2]));
+            self.fill_triangle(points, depth, pass, |_| color);
+        }
+    }
+
+    /// stroke_shader.wgsl
+    pub(crate) fn draw_stroke(
+        &mut self,
+        vertices: &[StrokeVertex],
+        indices: &[i32],
+        instance: &StrokeInstance,
+        pass: Pass,
+    ) {
+        let positions: Vec<_> = stroke_positions(vertices, instance)
+            .into_iter()
+            .map(|position| self.to_clip(position, instance.screen_space))
+            .collect();
+
+        let depth = 0.9 - ((instance.layer as f32 + 0.5) / 75000.0);
+        let color = instance.color;
+
+        for triangle in indices.chunks_exact(3) {
+            let points = [0, 1, 2].map(|i| (positions[triangle[i] as usize], [0.0; 2]));
+            self.fill_triangle(points, depth, pass, |_| color);
+        }
+    }
+
+    /// texture_shader.wgsl, the texture gets sampled with the nearest texel like the default sampler
+    pub(crate) fn draw_texture(
+        &mut self,
+        vertices: &[[f32; 2]],
+        indices: &[i32],
+        instance: &TextureInstance,
+        pages: &[RgbaImage],
+        pass: Pass,
+    ) {
+        let Some(page) = pages.get(instance.texture_page as usize) else {
+            return;
+        };
+
+        let half_scale = scale(instance.scale, 0.5);
+        let points: Vec<_> = vertices
+            .iter()
+            .map(|vertex| {
+                let local = [vertex[0] * half_scale[0], vertex[1] * half_scale[1]];
+                let position =
+                    self.to_clip(apply(&instance.transform, local), instance.screen_space);
+
+                let tex_coords = [
+                    instance.texture_position[0]
+                        + ((vertex[0] + 1.0) / 2.0) * instance.texture_scale[0],
+                    instance.texture_position[1]
+                        + (1.0 - (vertex[1] + 1.0) / 2.0) * instance.texture_scale[1],
+                ];
+
+                (position, tex_coords)
+            })
+            .collect();
+
+        let depth = 0.9 - (instance.layer as f32 / 75000.0);
+
+        for triangle in indices.chunks_exact(3) {
+            let points = [0, 1, 2].map(|i| points[triangle[i] as usize]);
+            self.fill_triangle(points, depth, pass, |tex_coords| {
+                let mut color = sample_nearest(page, tex_coords);
+                color[3] *= instance.alpha;
+                color
+            });
+        }
+    }
+
+    /// fills every pixel whose center is inside of the triangle
+    ///
+    /// the points are in normalized device coordinates with a value which gets interpolated,
+    /// nothing gets culled like in the pipelines because mirrored shapes turn their triangles around
+    fn fill_triangle(
+        &mut self,
+        points: [([f32; 2], [f32; 2]); 3],
+        depth: f32,
+        pass: Pass,
+        shade: impl Fn([f32; 2]) -> [f32; 4],
+    ) {
+        let [(a, _), (b, _), (c, _)] = points;
+        let points = match cross(sub(b, a), sub(c, a)) {
+            winding if winding > 0.0 => points,
+            winding if winding < 0.0 => [points[0], points[2], points[1]],
+            _ => return,
+        };
+        let [(a, _), (b, _), (c, _)] = points;
+
+        let (width, height) = self.image.dimensions();
+
+        // y points down in pixels which turns the triangle around
+        let to_pixel = |point: [f32; 2]| {
+            [
+                (point[0] + 1.0) / 2.0 * width as f32,
+                (1.0 - point[1]) / 2.0 * height as f32,
+            ]
+        };
+        let pixels = [to_pixel(a), to_pixel(c), to_pixel(b)];
+        let values = [points[0].1, points[2].1, points[1].1];
+
+        let area = edge(pixels[0], pixels[1], pixels[2]);
+        if area <= 0.0 {
+            return;
+        }
+
+        let min_x = pixels.iter().map(|p| p[0]).fold(f32::MAX, f32::min);
+        let max_x = pixels.iter().map(|p| p[0]).fold(f32::MIN, f32::max);
+        let min_y = pixels.iter().map(|p| p[1]).fold(f32::MAX, f32::min);
+        let max_y = pixels.iter().map(|p| p[1]).fold(f32::MIN, f32::max);
+
+        let start_x = (min_x - 0.5).ceil().max(0.0) as u32;
+        let end_x = ((max_x - 0.5).floor() + 1.0).clamp(0.0, width as f32) as u32;
+        let start_y = (min_y - 0.5).ceil().max(0.0) as u32;
+        let end_y = ((max_y - 0.5).floor() + 1.0).clamp(0.0, height as f32) as u32;
+
+        let edges = [(1, 2), (2, 0), (0, 1)];
+
+        for y in start_y..end_y {
+            for x in start_x..end_x {
+                let center = [x as f32 + 0.5, y as f32 + 0.5];
+
+                let weights = edges.map(|(from, to)| edge(pixels[from], pixels[to], center));
+
+                // pixels on a shared edge only belong to one of the triangles
+                let inside = edges.iter().zip(weights).all(|((from, to), weight)| {
+                    weight > 0.0 || (weight == 0.0 && is_top_left(pixels[*from], pixels[*to]))
+                });
+
+                if !inside {
+                    continue;
+                }
+
+                let index = (y * width + x) as usize;
+                let passes_depth = match pass {
+                    Pass::Opaque => depth < self.depth[index],
+                    Pass::Translucent => depth <= self.depth[index],
+                };
+
+                if !passes_depth {
+                    continue;
+                }
+
+                let value = [0, 1].map(|i| {
+                    (weights[0] * values[0][i]
+                        + weights[1] * values[1][i]
+                        + weights[2] * values[2][i])
+                        / area
+                });
+                let color = shade(value);
+
+                let pixel = self.image.get_pixel_mut(x, y);
+                match pass {
+                    Pass::Opaque => {
+                        pixel.0 = color.map(to_unorm);
+                        self.depth[index] = depth;
+                    }
+                    Pass::Translucent => pixel.0 = blend(color, pixel.0),
+                }
+            }
+        }
+    }
+}
+
+/// positive if the point is on the right side of the edge from a to b
+fn edge(a: [f32; 2], b: [f32; 2], point: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (point[1] - a[1]) - (b[1] - a[1]) * (point[0] - a[0])
+}
+
+fn is_top_left(a: [f32; 2], b: [f32; 2]) -> bool {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    dy < 0.0 || (dy == 0.0 && dx > 0.0)
+}
+
+pub(crate) fn sample_nearest(texture: &RgbaImage, tex_coords: [f32; 2]) -> [f32; 4] {
+    if texture.width() == 0 || texture.height() == 0 {
+        return [0.0; 4];
+    }
+
+    let texel = |coordinate: f32, size: u32| {
+        ((coordinate * size as f32).floor().max(0.0) as u32).min(size - 1)
+    };
+
+    let pixel = texture.get_pixel(
+        texel(tex_coords[0], texture.width()),
+        texel(tex_coords[1], texture.height()),
+    );
+
+    pixel.0.map(|channel| channel as f32 / 255.0)
+}
+
+/// [BlendState::ALPHA_BLENDING](wgpu::BlendState::ALPHA_BLENDING)
+fn blend(source: [f32; 4], destination: [u8; 4]) -> [u8; 4] {
+    let alpha = source[3].clamp(0.0, 1.0);
+    let destination = destination.map(|channel| channel as f32 / 255.0);
+
+    [
+        source[0] * alpha + destination[0] * (1.0 - alpha),
+        source[1] * alpha + destination[1] * (1.0 - alpha),
+        source[2] * alpha + destination[2] * (1.0 - alpha),
+        alpha + destination[3] * (1.0 - alpha),
+    ]
+    .map(to_unorm)
+}
+
+fn to_unorm(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
@@ -0,0 +1,129 @@
+use std::borrow::Cow;
+
+use wgpu::{
+    BindGroupLayout, BlendState, ColorTargetState, ColorWrites, DepthStencilState, Device,
+    FragmentState, FrontFace, MultisampleState, PipelineLayoutDescriptor, PolygonMode,
+    PrimitiveState, PrimitiveTopology, RenderPipeline, RenderPipelineDescriptor, ShaderModule,
+    ShaderModuleDescriptor, ShaderSource, TextureFormat, VertexBufferLayout, VertexState,
+};
+
+/// Builder for the [RenderPipeline] of the [ShapeRenderer](crate::shape_renderer::ShapeRenderer)
+///
+/// works like the one of wgpu_noboiler but supports multisampling
+pub(crate) struct RenderPipelineCreator<'a> {
+    device: &'a Device,
+    format: TextureFormat,
+
+    shader: ShaderModule,
+
+    vertex_buffers: Vec<VertexBufferLayout<'a>>,
+    bind_groups: Vec<&'a BindGroupLayout>,
+
+    depth_stencil: Option<DepthStencilState>,
+    blend_state: BlendState,
+    sample_count: u32,
+}
+
+impl<'a> RenderPipelineCreator<'a> {
+    /// creates an [RenderPipelineCreator] where the shader is based on the given code
+    pub(crate) fn from_shader_code(
+        shader_code: &str,
+        device: &'a Device,
+        format: TextureFormat,
+    ) -> RenderPipelineCreator<'a> {
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Render Pipeline Shader"),
+            source: ShaderSource::Wgsl(Cow::from(shader_code)),
+        });
+
+        RenderPipelineCreator {
+            device,
+            format,
+            shader,
+
+            vertex_buffers: vec![],
+            bind_groups: vec![],
+
+            depth_stencil: None,
+            blend_state: BlendState::REPLACE,
+            sample_count: 1,
+        }
+    }
+
+    /// adds a [VertexBufferLayout] to the used list
+    pub(crate) fn add_vertex_buffer(mut self, layout: VertexBufferLayout<'a>) -> Self {
+        self.vertex_buffers.push(layout);
+        self
+    }
+
+    /// adds a [BindGroupLayout] to the used list
+    pub(crate) fn add_bind_group(mut self, layout: &'a BindGroupLayout) -> Self {
+        self.bind_groups.push(layout);
+        self
+    }
+
+    /// sets the used [DepthStencilState]
+    pub(crate) fn depth_stencil(mut self, depth_stencil: DepthStencilState) -> Self {
+        self.depth_stencil = Some(depth_stencil);
+        self
+    }
+
+    /// sets the used [BlendState]
+    pub(crate) fn blend_state(mut self, blend_state: BlendState) -> Self {
+        self.blend_state = blend_state;
+        self
+    }
+
+    /// samples per pixel, has to match the render targets
+    pub(crate) fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    /// creates a [RenderPipeline]
+    pub(crate) fn build(&self) -> RenderPipeline {
+        let render_pipeline_layout =
+            self.device
+                .create_pipeline_layout(&PipelineLayoutDescriptor {
+                    label: Some("Render Pipeline Layout"),
+                    bind_group_layouts: &self.bind_groups[..],
+                    push_constant_ranges: &[],
+                });
+
+        self.device
+            .create_render_pipeline(&RenderPipelineDescriptor {
+                label: Some("Render Pipeline"),
+                layout: Some(&render_pipeline_layout),
+                vertex: VertexState {
+                    module: &self.shader,
+                    entry_point: "vs_main",
+                    buffers: &self.vertex_buffers[..],
+                },
+                fragment: Some(FragmentState {
+                    module: &self.shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(ColorTargetState {
+                        format: self.format,
+                        blend: Some(self.blend_state),
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState {
+                    topology: PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: self.depth_stencil.to_owned(),
+                multisample: MultisampleState {
+                    count: self.sample_count,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            })
+    }
+}
@@ -0,0 +1,90 @@
+use std::ops::Range;
+
+use bytemuck::Pod;
+use wgpu::{Device, Queue};
+
+use crate::render::dynamic_buffer::DynamicBuffer;
+
+/// instances which stay between frames, only the changed ones get uploaded again
+///
+/// every instance has an owner, removing one moves the last instance into the gap
+pub(crate) struct RetainedBuffer<T> {
+    label: &'static str,
+    instances: Vec<T>,
+    owners: Vec<u32>,
+    /// instances which changed since the last upload
+    dirty: Option<Range<usize>>,
+    buffer: Option<DynamicBuffer>,
+}
+
+impl<T: Pod> RetainedBuffer<T> {
+    pub(crate) fn new(label: &'static str) -> Self {
+        RetainedBuffer {
+            label,
+            instances: vec![],
+            owners: vec![],
+            dirty: None,
+            buffer: None,
+        }
+    }
+
+    /// adds the instance and returns its position
+    pub(crate) fn push(&mut self, owner: u32, instance: T) -> u32 {
+        self.instances.push(instance);
+        self.owners.push(owner);
+
+        let position = self.instances.len() - 1;
+        self.mark(position);
+        position as u32
+    }
+
+    /// removes the instance at the position and returns the owner of the instance which took its place
+    pub(crate) fn swap_remove(&mut self, position: u32) -> Option<u32> {
+        let position = position as usize;
+
+        self.instances.swap_remove(position);
+        self.owners.swap_remove(position);
+
+        if position == self.instances.len() {
+            return None;
+        }
+
+        self.mark(position);
+        Some(self.owners[position])
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.instances.clear();
+        self.owners.clear();
+        self.dirty = None;
+    }
+
+    fn mark(&mut self, position: usize) {
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.start.min(position)..dirty.end.max(position + 1),
+            None => position..position + 1,
+        });
+    }
+
+    /// uploads the changed instances
+    pub(crate) fn flush(&mut self, device: &Device, queue: &Queue) {
+        if self.buffer.is_none() && self.instances.is_empty() {
+            return;
+        }
+
+        let label = self.label;
+        let buffer = self
+            .buffer
+            .get_or_insert_with(|| DynamicBuffer::vertex(device, label));
+
+        let dirty = self.dirty.take().unwrap_or_default();
+        let dirty = dirty.start.min(self.instances.len())..dirty.end.min(self.instances.len());
+
+        buffer.write_range(device, queue, &self.instances, dirty);
+    }
+
+    /// the gpu buffer if something was uploaded so far
+    pub(crate) fn buffer(&self) -> Option<&DynamicBuffer> {
+        self.buffer.as_ref().filter(|buffer| buffer.size() > 0)
+    }
+}
@@ -0,0 +1,95 @@
+use crate::math::{dot, normalize, perpendicular, scale, sub};
+use crate::render::instance::StrokeInstance;
+use crate::render::mesh::Mesh;
+use crate::transform::apply;
+
+/// point of a stroke mesh
+///
+/// the neighbours of the point along the outline are needed
+/// so the shader can offset it after the shape got scaled
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct StrokeVertex {
+    pub(crate) position: [f32; 2],
+    pub(crate) previous: [f32; 2],
+    pub(crate) next: [f32; 2],
+    /// 0.0 -> on the outline, 1.0 -> moved inwards by the stroke width
+    pub(crate) inset: f32,
+}
+
+impl wgpu_noboiler::vertex::Vertex<4> for StrokeVertex {
+    const ATTRIBS: [wgpu::VertexAttribute; 4] =
+        wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x2, 3 => Float32];
+}
+
+/// creates a closed stroke along the given outline
+///
+/// the stroke is on the left side of the outline -> inside of counter clockwise outlines
+pub(crate) fn stroke_mesh(outline: &[[f32; 2]]) -> Mesh<StrokeVertex> {
+    let count = outline.len();
+
+    let vertices = (0..count)
+        .flat_map(|i| {
+            let vertex = StrokeVertex {
+                position: outline[i],
+                previous: outline[(i + count - 1) % count],
+                next: outline[(i + 1) % count],
+                inset: 0.0,
+            };
+
+            [
+                vertex,
+                StrokeVertex {
+                    inset: 1.0,
+                    ..vertex
+                },
+            ]
+        })
+        .collect();
+
+    let indices = (0..count as i32)
+        .flat_map(|i| {
+            let outer = i * 2;
+            let inner = outer + 1;
+            let next_outer = (i + 1) % count as i32 * 2;
+            let next_inner = next_outer + 1;
+
+            [outer, next_outer, next_inner, outer, next_inner, inner]
+        })
+        .collect();
+
+    Mesh { vertices, indices }
+}
+
+/// stroke_shader.wgsl, the vertices in the frame after they got moved inwards by the stroke width
+pub(crate) fn stroke_positions(
+    vertices: &[StrokeVertex],
+    instance: &StrokeInstance,
+) -> Vec<[f32; 2]> {
+    let half_scale = scale(instance.scale, 0.5);
+    let width = instance
+        .width
+        .min(half_scale[0].abs().min(half_scale[1].abs()));
+
+    vertices
+        .iter()
+        .map(|vertex| {
+            let scaled = |point: [f32; 2]| [point[0] * half_scale[0], point[1] * half_scale[1]];
+
+            let current = scaled(vertex.position);
+            let before = perpendicular(normalize(sub(current, scaled(vertex.previous))));
+            let after = perpendicular(normalize(sub(scaled(vertex.next), current)));
+
+            let miter = scale(
+                [before[0] + after[0], before[1] + after[1]],
+                1.0 / (1.0 + dot(before, after)),
+            );
+
+            let local = [
+                current[0] + miter[0] * width * vertex.inset,
+                current[1] + miter[1] * width * vertex.inset,
+            ];
+            apply(&instance.transform, local)
+        })
+        .collect()
+}
@@ -0,0 +1,183 @@
+use crate::math::{cross, sub};
+use crate::render::mesh::Mesh;
+
+/// doubled signed area of the outline
+///
+/// positive -> counter clockwise
+pub(crate) fn signed_area(outline: &[[f32; 2]]) -> f32 {
+    (0..outline.len())
+        .map(|i| cross(outline[i], outline[(i + 1) % outline.len()]))
+        .sum()
+}
+
+/// triangulates a simple polygon which can be concave and can contain holes
+///
+/// uses ear clipping after every hole got connected to the outline
+pub(crate) fn triangulate(outline: &[[f32; 2]], holes: &[Vec<[f32; 2]>]) -> Mesh {
+    let mut mesh = Mesh::default();
+
+    if outline.len() < 3 {
+        return mesh;
+    }
+
+    let mut ring: Vec<i32> = outline
+        .iter()
+        .map(|point| mesh.add_vertex(*point))
+        .collect();
+    if signed_area(outline) < 0.0 {
+        ring.reverse();
+    }
+
+    let mut holes: Vec<Vec<i32>> = holes
+        .iter()
+        .filter(|hole| hole.len() >= 3)
+        .map(|hole| {
+            let mut indices: Vec<i32> = hole.iter().map(|point| mesh.add_vertex(*point)).collect();
+            if signed_area(hole) > 0.0 {
+                indices.reverse();
+            }
+            indices
+        })
+        .collect();
+
+    // holes which reach further right have to be connected first
+    holes.sort_by(|a, b| max_x(&mesh, b).total_cmp(&max_x(&mesh, a)));
+
+    for hole in holes {
+        bridge_hole(&mesh, &mut ring, &hole);
+    }
+
+    clip_ears(&mut mesh, ring);
+
+    mesh
+}
+
+fn position(mesh: &Mesh, index: i32) -> [f32; 2] {
+    mesh.vertices[index as usize].position
+}
+
+fn max_x(mesh: &Mesh, ring: &[i32]) -> f32 {
+    ring.iter()
+        .map(|index| position(mesh, *index)[0])
+        .fold(f32::MIN, f32::max)
+}
+
+/// connects the hole to a visible vertex of the ring so both form one outline
+fn bridge_hole(mesh: &Mesh, ring: &mut Vec<i32>, hole: &[i32]) {
+    let (hole_start, hole_point) = hole
+        .iter()
+        .map(|index| position(mesh, *index))
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a[0].total_cmp(&b[0]))
+        .unwrap();
+
+    // closest edge of the ring hit by a ray going right from the hole
+    let mut closest: Option<(f32, usize)> = None;
+
+    for i in 0..ring.len() {
+        let a = position(mesh, ring[i]);
+        let b = position(mesh, ring[(i + 1) % ring.len()]);
+
+        if (a[1] > hole_point[1]) == (b[1] > hole_point[1]) {
+            continue;
+        }
+
+        let x = a[0] + (hole_point[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+        if x < hole_point[0] || closest.is_some_and(|(closest, _)| x >= closest) {
+            continue;
+        }
+
+        let candidate = if a[0] > b[0] { i } else { (i + 1) % ring.len() };
+        closest = Some((x, candidate));
+    }
+
+    let Some((hit_x, mut bridge)) = closest else {
+        return;
+    };
+
+    // a reflex vertex inside the triangle between hole, hit and candidate could block the view
+    let hit = [hit_x, hole_point[1]];
+    let candidate = position(mesh, ring[bridge]);
+    let mut best_angle = f32::MAX;
+
+    for i in 0..ring.len() {
+        let point = position(mesh, ring[i]);
+        if !point_in_triangle(point, hole_point, hit, candidate) || point == candidate {
+            continue;
+        }
+
+        let previous = position(mesh, ring[(i + ring.len() - 1) % ring.len()]);
+        let next = position(mesh, ring[(i + 1) % ring.len()]);
+        if cross(sub(point, previous), sub(next, point)) > 0.0 {
+            continue;
+        }
+
+        let direction = sub(point, hole_point);
+        let angle = (direction[1].abs()).atan2(direction[0]);
+        if angle < best_angle {
+            best_angle = angle;
+            bridge = i;
+        }
+    }
+
+    let mut bridged = Vec::with_capacity(ring.len() + hole.len() + 2);
+    bridged.extend_from_slice(&ring[..=bridge]);
+    bridged.extend(hole.iter().cycle().skip(hole_start).take(hole.len() + 1));
+    bridged.extend_from_slice(&ring[bridge..]);
+
+    *ring = bridged;
+}
+
+fn clip_ears(mesh: &mut Mesh, mut ring: Vec<i32>) {
+    let mut misses = 0;
+    let mut i = 0;
+
+    while ring.len() > 3 {
+        let length = ring.len();
+        let previous = ring[(i + length - 1) % length];
+        let current = ring[i % length];
+        let next = ring[(i + 1) % length];
+
+        let a = position(mesh, previous);
+        let b = position(mesh, current);
+        let c = position(mesh, next);
+
+        let turn = cross(sub(b, a), sub(c, b));
+
+        // collinear points do not form a triangle
+        if turn.abs() <= f32::EPSILON {
+            ring.remove(i % length);
+            misses = 0;
+            continue;
+        }
+
+        // gives up on invalid outlines instead of looping forever
+        let is_ear = misses >= length
+            || (turn > 0.0
+                && !ring.iter().any(|index| {
+                    let point = position(mesh, *index);
+                    point != a && point != b && point != c && point_in_triangle(point, a, b, c)
+                }));
+
+        if is_ear {
+            mesh.add_triangle(previous, current, next);
+            ring.remove(i % length);
+            misses = 0;
+        } else {
+            i = (i + 1) % length;
+            misses += 1;
+        }
+    }
+
+    if ring.len() == 3 {
+        mesh.add_triangle(ring[0], ring[1], ring[2]);
+    }
+}
+
+fn point_in_triangle(point: [f32; 2], a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> bool {
+    let ab = cross(sub(b, a), sub(point, a));
+    let bc = cross(sub(c, b), sub(point, b));
+    let ca = cross(sub(a, c), sub(point, c));
+
+    (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0)
+}
@@ -0,0 +1,743 @@
+//! shapes which stay between frames and get changed through a [ShapeHandle]
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use wgpu::{Device, Queue};
+
+use crate::render::atlas::TextureAtlas;
+use crate::render::instance::{Instance, StrokeInstance, TextureInstance};
+use crate::render::retained_buffer::RetainedBuffer;
+use crate::shape::group::Group;
+use crate::shape::image::Image;
+use crate::shape::oval::Oval;
+use crate::shape::rect::Rect;
+use crate::shape::shapes::{BasicShape, BasicShapeData};
+use crate::transform::{multiply, Affine};
+
+/// reference to a shape added with [add_rect](crate::shape_renderer::ShapeRenderer::add_rect),
+/// [add_oval](crate::shape_renderer::ShapeRenderer::add_oval) or [add_image](crate::shape_renderer::ShapeRenderer::add_image)
+///
+/// stays valid until the shape gets removed, the handle of a removed shape never points to another shape
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShapeHandle {
+    index: u32,
+    generation: u32,
+}
+
+/// reference to a [Group] added with [add_group](crate::shape_renderer::ShapeRenderer::add_group)
+///
+/// stays valid until the group gets removed
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GroupHandle {
+    index: u32,
+    generation: u32,
+}
+
+#[derive(Clone)]
+pub(crate) enum RetainedShape {
+    Rect(Rect),
+    Oval(Oval),
+    Image(Image),
+}
+
+impl RetainedShape {
+    /// the shape as it is seen from outside of the groups it is in
+    fn transformed(&self, transform: &GroupTransform) -> RetainedShape {
+        let mut shape = self.clone();
+
+        match &mut shape {
+            RetainedShape::Rect(Rect { data }) | RetainedShape::Oval(Oval { data, .. }) => {
+                transform.apply_to_data(data)
+            }
+            RetainedShape::Image(image) => {
+                image.transform = multiply(&transform.matrix, &image.transform);
+                image.layer = image.layer.saturating_add(transform.layer);
+            }
+        }
+
+        shape
+    }
+}
+
+/// every group a shape is in combined
+#[derive(Copy, Clone)]
+struct GroupTransform {
+    matrix: Affine,
+    layer: u16,
+}
+
+impl GroupTransform {
+    fn apply_to_data(&self, data: &mut BasicShapeData) {
+        data.transform = multiply(&self.matrix, &data.transform);
+        data.layer = data.layer.saturating_add(self.layer);
+    }
+}
+
+/// gpu buffer an instance of a shape is in
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Target {
+    Rect,
+    RectStroke,
+    Oval(u32),
+    OvalStroke(u32),
+    Image,
+}
+
+struct Entry {
+    shape: RetainedShape,
+    /// the shape transformed by its groups when it got placed, None if it is in no group
+    world: Option<RetainedShape>,
+    visible: bool,
+    dirty: bool,
+    /// positions of the opaque instances in the buffers
+    placement: Vec<(Target, u32)>,
+    parent: Option<u32>,
+}
+
+struct Slot {
+    generation: u32,
+    entry: Option<Entry>,
+}
+
+struct GroupEntry {
+    group: Group,
+    /// this group and every group above it combined, None until it is needed again after a change
+    world: Cell<Option<GroupTransform>>,
+    parent: Option<u32>,
+    shapes: Vec<u32>,
+    groups: Vec<u32>,
+}
+
+struct GroupSlot {
+    generation: u32,
+    entry: Option<GroupEntry>,
+}
+
+/// opaque instances of the ovals with one segment count
+pub(crate) struct RetainedOvals {
+    pub(crate) fill: RetainedBuffer<Instance>,
+    pub(crate) stroke: RetainedBuffer<StrokeInstance>,
+}
+
+/// every retained shape and the buffers of its opaque instances
+///
+/// translucent shapes and shapes drawn in [RenderMode::Sdf](crate::shape_renderer::RenderMode::Sdf)
+/// get sorted together with the other shapes every frame,
+/// they are tracked when shapes get placed so the other shapes are not visited then
+pub(crate) struct RetainedShapes {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    dirty: Vec<u32>,
+    sdf: bool,
+    /// visible shapes with a translucent fill, stroke or image
+    translucent: BTreeSet<u32>,
+    /// visible rects and ovals while the sdf shader draws them
+    sdf_shapes: BTreeSet<u32>,
+    /// segment counts of the ovals which got placed
+    oval_details: HashSet<u32>,
+    groups: Vec<GroupSlot>,
+    free_groups: Vec<u32>,
+
+    pub(crate) rects: RetainedBuffer<Instance>,
+    pub(crate) rect_strokes: RetainedBuffer<StrokeInstance>,
+    pub(crate) ovals: HashMap<u32, RetainedOvals>,
+    pub(crate) images: RetainedBuffer<TextureInstance>,
+}
+
+impl RetainedShapes {
+    pub(crate) fn new() -> Self {
+        RetainedShapes {
+            slots: vec![],
+            free: vec![],
+            dirty: vec![],
+            sdf: false,
+            translucent: BTreeSet::new(),
+            sdf_shapes: BTreeSet::new(),
+            oval_details: HashSet::new(),
+            groups: vec![],
+            free_groups: vec![],
+            rects: RetainedBuffer::new("Retained Rect InstanceBuffer"),
+            rect_strokes: RetainedBuffer::new("Retained Rect Stroke InstanceBuffer"),
+            ovals: HashMap::new(),
+            images: RetainedBuffer::new("Retained Image InstanceBuffer"),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, shape: RetainedShape) -> (ShapeHandle, &mut RetainedShape) {
+        let entry = Entry {
+            shape,
+            world: None,
+            visible: true,
+            dirty: true,
+            placement: vec![],
+            parent: None,
+        };
+
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    entry: None,
+                });
+                self.slots.len() as u32 - 1
+            }
+        };
+
+        self.dirty.push(index);
+
+        let slot = &mut self.slots[index as usize];
+        let handle = ShapeHandle {
+            index,
+            generation: slot.generation,
+        };
+
+        (handle, &mut slot.entry.insert(entry).shape)
+    }
+
+    fn entry(&self, handle: ShapeHandle) -> Option<&Entry> {
+        let slot = self.slots.get(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+
+        slot.entry.as_ref()
+    }
+
+    /// marks the shape as changed, it gets placed again before the next frame
+    fn entry_mut(&mut self, handle: ShapeHandle) -> Option<&mut Entry> {
+        let slot = self.slots.get_mut(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+
+        let entry = slot.entry.as_mut()?;
+        if !entry.dirty {
+            entry.dirty = true;
+            self.dirty.push(handle.index);
+        }
+
+        Some(entry)
+    }
+
+    fn mark_dirty(&mut self, index: u32) {
+        if let Some(entry) = &mut self.slots[index as usize].entry {
+            if !entry.dirty {
+                entry.dirty = true;
+                self.dirty.push(index);
+            }
+        }
+    }
+
+    pub(crate) fn contains(&self, handle: ShapeHandle) -> bool {
+        self.entry(handle).is_some()
+    }
+
+    pub(crate) fn get_mut(&mut self, handle: ShapeHandle) -> Option<&mut RetainedShape> {
+        Some(&mut self.entry_mut(handle)?.shape)
+    }
+
+    pub(crate) fn is_visible(&self, handle: ShapeHandle) -> bool {
+        self.entry(handle).is_some_and(|entry| entry.visible)
+    }
+
+    pub(crate) fn set_visible(&mut self, handle: ShapeHandle, visible: bool) {
+        if self
+            .entry(handle)
+            .is_some_and(|entry| entry.visible != visible)
+        {
+            self.entry_mut(handle).unwrap().visible = visible;
+        }
+    }
+
+    /// removes the shape and its instances, false if the handle was not valid
+    pub(crate) fn remove(&mut self, handle: ShapeHandle) -> bool {
+        if !self.contains(handle) {
+            return false;
+        }
+
+        self.remove_index(handle.index);
+        true
+    }
+
+    fn remove_index(&mut self, index: u32) {
+        self.unplace(index);
+        self.detach(index);
+
+        let slot = &mut self.slots[index as usize];
+        slot.entry = None;
+        slot.generation += 1;
+        self.free.push(index);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.entry.take().is_some() {
+                slot.generation += 1;
+                self.free.push(index as u32);
+            }
+        }
+
+        for (index, slot) in self.groups.iter_mut().enumerate() {
+            if slot.entry.take().is_some() {
+                slot.generation += 1;
+                self.free_groups.push(index as u32);
+            }
+        }
+
+        self.dirty.clear();
+        self.translucent.clear();
+        self.sdf_shapes.clear();
+        self.rects.clear();
+        self.rect_strokes.clear();
+        self.images.clear();
+        for ovals in self.ovals.values_mut() {
+            ovals.fill.clear();
+            ovals.stroke.clear();
+        }
+    }
+
+    /// rects and ovals are not in the opaque buffers while the sdf shader draws them
+    pub(crate) fn set_sdf(&mut self, sdf: bool) {
+        if self.sdf == sdf {
+            return;
+        }
+
+        self.sdf = sdf;
+        self.mark_all_dirty();
+    }
+
+    /// places every image again after the atlas changed, images whose texture is gone stop being drawn
+    pub(crate) fn update_textures(&mut self) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            let Some(entry) = &mut slot.entry else {
+                continue;
+            };
+
+            if !matches!(entry.shape, RetainedShape::Image(_)) {
+                continue;
+            }
+
+            if !entry.dirty {
+                entry.dirty = true;
+                self.dirty.push(index as u32);
+            }
+        }
+    }
+
+    fn mark_all_dirty(&mut self) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if let Some(entry) = &mut slot.entry {
+                if !entry.dirty {
+                    entry.dirty = true;
+                    self.dirty.push(index as u32);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn insert_group(
+        &mut self,
+        parent: Option<GroupHandle>,
+    ) -> (GroupHandle, &mut Group) {
+        let parent = parent.filter(|parent| self.group_entry(*parent).is_some());
+
+        let index = match self.free_groups.pop() {
+            Some(index) => index,
+            None => {
+                self.groups.push(GroupSlot {
+                    generation: 0,
+                    entry: None,
+                });
+                self.groups.len() as u32 - 1
+            }
+        };
+
+        if let Some(parent) = parent {
+            self.group_entry_mut(parent).unwrap().groups.push(index);
+        }
+
+        let slot = &mut self.groups[index as usize];
+        let handle = GroupHandle {
+            index,
+            generation: slot.generation,
+        };
+
+        let entry = slot.entry.insert(GroupEntry {
+            group: Group::default(),
+            world: Cell::new(None),
+            parent: parent.map(|parent| parent.index),
+            shapes: vec![],
+            groups: vec![],
+        });
+
+        (handle, &mut entry.group)
+    }
+
+    fn group_entry(&self, handle: GroupHandle) -> Option<&GroupEntry> {
+        let slot = self.groups.get(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+
+        slot.entry.as_ref()
+    }
+
+    fn group_entry_mut(&mut self, handle: GroupHandle) -> Option<&mut GroupEntry> {
+        let slot = self.groups.get_mut(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+
+        slot.entry.as_mut()
+    }
+
+    pub(crate) fn contains_group(&self, handle: GroupHandle) -> bool {
+        self.group_entry(handle).is_some()
+    }
+
+    /// every shape inside of the group gets placed again before the next frame
+    pub(crate) fn group_mut(&mut self, handle: GroupHandle) -> Option<&mut Group> {
+        self.group_entry(handle)?;
+        self.mark_group_dirty(handle.index);
+
+        Some(&mut self.group_entry_mut(handle)?.group)
+    }
+
+    fn mark_group_dirty(&mut self, index: u32) {
+        let entry = self.groups[index as usize].entry.as_ref().unwrap();
+        entry.world.set(None);
+        let (shapes, groups) = (entry.shapes.clone(), entry.groups.clone());
+
+        for shape in shapes {
+            self.mark_dirty(shape);
+        }
+        for group in groups {
+            self.mark_group_dirty(group);
+        }
+    }
+
+    /// removes the group with all groups and shapes inside of it
+    pub(crate) fn remove_group(&mut self, handle: GroupHandle) -> bool {
+        if !self.contains_group(handle) {
+            return false;
+        }
+
+        if let Some(parent) = self.groups[handle.index as usize]
+            .entry
+            .as_ref()
+            .unwrap()
+            .parent
+        {
+            let parent = self.groups[parent as usize].entry.as_mut().unwrap();
+            parent.groups.retain(|group| *group != handle.index);
+        }
+
+        self.remove_group_index(handle.index);
+        true
+    }
+
+    fn remove_group_index(&mut self, index: u32) {
+        let slot = &mut self.groups[index as usize];
+        let entry = slot.entry.take().unwrap();
+        slot.generation += 1;
+        self.free_groups.push(index);
+
+        for shape in entry.shapes {
+            // already not in the list of the group anymore
+            self.slots[shape as usize].entry.as_mut().unwrap().parent = None;
+            self.remove_index(shape);
+        }
+        for group in entry.groups {
+            self.remove_group_index(group);
+        }
+    }
+
+    /// moves the shape into the group, None -> not in a group
+    pub(crate) fn set_parent(&mut self, handle: ShapeHandle, parent: Option<GroupHandle>) -> bool {
+        if !self.contains(handle) || parent.is_some_and(|parent| !self.contains_group(parent)) {
+            return false;
+        }
+
+        self.detach(handle.index);
+
+        if let Some(parent) = parent {
+            self.group_entry_mut(parent)
+                .unwrap()
+                .shapes
+                .push(handle.index);
+        }
+
+        self.entry_mut(handle).unwrap().parent = parent.map(|parent| parent.index);
+        true
+    }
+
+    /// removes the shape from the list of its group
+    fn detach(&mut self, index: u32) {
+        let entry = self.slots[index as usize].entry.as_mut().unwrap();
+
+        if let Some(parent) = entry.parent.take() {
+            let parent = self.groups[parent as usize].entry.as_mut().unwrap();
+            parent.shapes.retain(|shape| *shape != index);
+        }
+    }
+
+    /// every group above the shape combined, None -> not in a group
+    fn group_transform(&self, entry: &Entry) -> Option<GroupTransform> {
+        Some(self.group_world(entry.parent?))
+    }
+
+    /// the group and every group above it combined, gets cached until one of them changes
+    fn group_world(&self, index: u32) -> GroupTransform {
+        let entry = self.groups[index as usize].entry.as_ref().unwrap();
+        if let Some(world) = entry.world.get() {
+            return world;
+        }
+
+        let matrix = entry.group.matrix();
+        let world = match entry.parent {
+            Some(parent) => {
+                let parent = self.group_world(parent);
+                GroupTransform {
+                    matrix: multiply(&parent.matrix, &matrix),
+                    layer: parent.layer.saturating_add(entry.group.layer),
+                }
+            }
+            None => GroupTransform {
+                matrix,
+                layer: entry.group.layer,
+            },
+        };
+
+        entry.world.set(Some(world));
+        world
+    }
+
+    /// the shape as it gets drawn, only changed shapes get transformed again
+    fn world_shape<'a>(&self, entry: &'a Entry) -> Cow<'a, RetainedShape> {
+        if !entry.dirty {
+            return Cow::Borrowed(Self::placed_shape(entry));
+        }
+
+        match self.group_transform(entry) {
+            Some(transform) => Cow::Owned(entry.shape.transformed(&transform)),
+            None => Cow::Borrowed(&entry.shape),
+        }
+    }
+
+    /// the shape as it was when it got placed
+    fn placed_shape(entry: &Entry) -> &RetainedShape {
+        entry.world.as_ref().unwrap_or(&entry.shape)
+    }
+
+    /// every visible shape as it gets drawn
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (ShapeHandle, Cow<'_, RetainedShape>)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let entry = slot.entry.as_ref().filter(|entry| entry.visible)?;
+            let handle = ShapeHandle {
+                index: index as u32,
+                generation: slot.generation,
+            };
+            Some((handle, self.world_shape(entry)))
+        })
+    }
+
+    /// the placed shapes of the set in the order they were added
+    fn placed<'a>(&'a self, set: &'a BTreeSet<u32>) -> impl Iterator<Item = &'a RetainedShape> {
+        set.iter()
+            .map(|index| Self::placed_shape(self.slots[*index as usize].entry.as_ref().unwrap()))
+    }
+
+    /// visible rects with a translucent fill or stroke, up to date after [update](RetainedShapes::update)
+    pub(crate) fn translucent_rects(&self) -> impl Iterator<Item = &Rect> {
+        self.placed(&self.translucent)
+            .filter_map(|shape| match shape {
+                RetainedShape::Rect(rect) => Some(rect),
+                _ => None,
+            })
+    }
+
+    /// visible ovals with a translucent fill or stroke, up to date after [update](RetainedShapes::update)
+    pub(crate) fn translucent_ovals(&self) -> impl Iterator<Item = &Oval> {
+        self.placed(&self.translucent)
+            .filter_map(|shape| match shape {
+                RetainedShape::Oval(oval) => Some(oval),
+                _ => None,
+            })
+    }
+
+    /// visible translucent images, up to date after [update](RetainedShapes::update)
+    pub(crate) fn translucent_images(&self) -> impl Iterator<Item = &Image> {
+        self.placed(&self.translucent)
+            .filter_map(|shape| match shape {
+                RetainedShape::Image(image) => Some(image),
+                _ => None,
+            })
+    }
+
+    /// visible rects while the sdf shader draws them, up to date after [update](RetainedShapes::update)
+    pub(crate) fn sdf_rects(&self) -> impl Iterator<Item = &Rect> {
+        self.placed(&self.sdf_shapes)
+            .filter_map(|shape| match shape {
+                RetainedShape::Rect(rect) => Some(rect),
+                _ => None,
+            })
+    }
+
+    /// visible ovals while the sdf shader draws them, up to date after [update](RetainedShapes::update)
+    pub(crate) fn sdf_ovals(&self) -> impl Iterator<Item = &Oval> {
+        self.placed(&self.sdf_shapes)
+            .filter_map(|shape| match shape {
+                RetainedShape::Oval(oval) => Some(oval),
+                _ => None,
+            })
+    }
+
+    /// places the changed shapes into the buffers and uploads what changed
+    pub(crate) fn update(&mut self, device: &Device, queue: &Queue, atlas: &TextureAtlas) {
+        for index in std::mem::take(&mut self.dirty) {
+            let Some(entry) = &self.slots[index as usize].entry else {
+                continue;
+            };
+
+            let world = self
+                .group_transform(entry)
+                .map(|transform| entry.shape.transformed(&transform));
+
+            let entry = self.slots[index as usize].entry.as_mut().unwrap();
+            entry.world = world;
+            entry.dirty = false;
+
+            self.unplace(index);
+            self.place(index, atlas);
+        }
+
+        self.rects.flush(device, queue);
+        self.rect_strokes.flush(device, queue);
+        self.images.flush(device, queue);
+        for ovals in self.ovals.values_mut() {
+            ovals.fill.flush(device, queue);
+            ovals.stroke.flush(device, queue);
+        }
+    }
+
+    /// adds the opaque instances of the shape to the buffers
+    fn place(&mut self, index: u32, atlas: &TextureAtlas) {
+        let entry = self.slots[index as usize].entry.as_ref().unwrap();
+        if !entry.visible {
+            return;
+        }
+
+        let mut fill = None;
+        let mut stroke = None;
+        let mut image = None;
+        let mut sdf_shape = false;
+        let mut oval_detail = None;
+
+        match Self::placed_shape(entry) {
+            RetainedShape::Rect(_) | RetainedShape::Oval(_) if self.sdf => sdf_shape = true,
+            RetainedShape::Rect(rect) => {
+                fill = rect.data.fill.then(|| (Target::Rect, rect.to_instance()));
+                stroke = rect
+                    .to_stroke_instance()
+                    .map(|instance| (Target::RectStroke, instance));
+            }
+            RetainedShape::Oval(oval) => {
+                oval_detail = Some(oval.detail);
+                fill = oval
+                    .data
+                    .fill
+                    .then(|| (Target::Oval(oval.detail), oval.to_instance()));
+                stroke = oval
+                    .to_stroke_instance()
+                    .map(|instance| (Target::OvalStroke(oval.detail), instance));
+            }
+            RetainedShape::Image(shape) => image = shape.to_instance(atlas),
+        }
+
+        if sdf_shape {
+            self.sdf_shapes.insert(index);
+        }
+        if let Some(detail) = oval_detail {
+            self.oval_details.insert(detail);
+        }
+
+        let translucent = fill.is_some_and(|(_, instance)| instance.is_translucent())
+            || stroke.is_some_and(|(_, instance)| instance.is_translucent())
+            || image.is_some_and(|instance| instance.is_translucent());
+        if translucent {
+            self.translucent.insert(index);
+        }
+
+        let mut placement = vec![];
+
+        if let Some((target, instance)) = fill.filter(|(_, instance)| !instance.is_translucent()) {
+            let position = match target {
+                Target::Oval(detail) => self.oval_buffers(detail).fill.push(index, instance),
+                _ => self.rects.push(index, instance),
+            };
+            placement.push((target, position));
+        }
+
+        if let Some((target, instance)) = stroke.filter(|(_, instance)| !instance.is_translucent())
+        {
+            let position = match target {
+                Target::OvalStroke(detail) => {
+                    self.oval_buffers(detail).stroke.push(index, instance)
+                }
+                _ => self.rect_strokes.push(index, instance),
+            };
+            placement.push((target, position));
+        }
+
+        if let Some(instance) = image.filter(|instance| !instance.is_translucent()) {
+            placement.push((Target::Image, self.images.push(index, instance)));
+        }
+
+        self.slots[index as usize].entry.as_mut().unwrap().placement = placement;
+    }
+
+    fn oval_buffers(&mut self, detail: u32) -> &mut RetainedOvals {
+        self.ovals.entry(detail).or_insert_with(|| RetainedOvals {
+            fill: RetainedBuffer::new("Retained Oval InstanceBuffer"),
+            stroke: RetainedBuffer::new("Retained Oval Stroke InstanceBuffer"),
+        })
+    }
+
+    /// removes the instances of the shape from the buffers
+    fn unplace(&mut self, index: u32) {
+        self.translucent.remove(&index);
+        self.sdf_shapes.remove(&index);
+
+        let entry = self.slots[index as usize].entry.as_mut().unwrap();
+
+        for (target, position) in std::mem::take(&mut entry.placement) {
+            let moved = match target {
+                Target::Rect => self.rects.swap_remove(position),
+                Target::RectStroke => self.rect_strokes.swap_remove(position),
+                Target::Oval(detail) => self.oval_buffers(detail).fill.swap_remove(position),
+                Target::OvalStroke(detail) => {
+                    self.oval_buffers(detail).stroke.swap_remove(position)
+                }
+                Target::Image => self.images.swap_remove(position),
+            };
+
+            // the last instance of the buffer took the free position
+            if let Some(owner) = moved {
+                let owner = self.slots[owner as usize].entry.as_mut().unwrap();
+                for placed in &mut owner.placement {
+                    if placed.0 == target {
+                        placed.1 = position;
+                    }
+                }
+            }
+        }
+    }
+
+    /// segment counts of the placed ovals, their meshes have to exist
+    pub(crate) fn oval_details(&self) -> impl Iterator<Item = u32> + '_ {
+        self.oval_details.iter().copied()
+    }
+}
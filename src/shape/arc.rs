@@ -0,0 +1,256 @@
+use std::f32::consts::PI;
+
+use crate::math::scale;
+use crate::render::mesh::Mesh;
+use crate::render::stroke::{stroke_mesh, StrokeVertex};
+use crate::shape::oval::oval_points;
+use crate::shape::shapes::{BasicShape, BasicShapeData};
+
+/// part of an oval between two angles and two radii
+///
+/// angles are in radians counter clockwise starting at the right,
+/// the inner radius is relative to the outer one which is given by the [scale](BasicShape::scale)
+pub(crate) struct ArcGeometry {
+    pub(crate) start_angle: f32,
+    pub(crate) end_angle: f32,
+    pub(crate) inner_radius: f32,
+    pub(crate) detail: u32,
+}
+
+impl ArcGeometry {
+    /// counter clockwise points of the outer and the inner edge
+    ///
+    /// (-1,-1) -> bottom left, (1,1) -> top right
+    fn edges(&self) -> (Vec<[f32; 2]>, Vec<[f32; 2]>, bool) {
+        let sweep = self.end_angle - self.start_angle;
+
+        // always goes counter clockwise from the start to the end
+        let full = sweep.abs() >= PI * 2.0 - f32::EPSILON;
+        let sweep = if full {
+            PI * 2.0
+        } else {
+            sweep.rem_euclid(PI * 2.0)
+        };
+
+        let mut outer = oval_points(self.detail, self.start_angle, sweep);
+
+        if full {
+            outer.pop();
+        }
+
+        let inner_radius = self.inner_radius.clamp(0.0, 1.0);
+        let inner = outer
+            .iter()
+            .map(|point| scale(*point, inner_radius))
+            .collect();
+
+        (outer, inner, full)
+    }
+
+    fn has_hole(&self) -> bool {
+        self.inner_radius > 0.0
+    }
+
+    pub(crate) fn to_mesh(&self) -> Mesh {
+        let (outer, inner, full) = self.edges();
+        let mut mesh = Mesh::default();
+
+        let outer: Vec<i32> = outer.into_iter().map(|p| mesh.add_vertex(p)).collect();
+        let segments = if full { outer.len() } else { outer.len() - 1 };
+
+        if self.has_hole() {
+            let inner: Vec<i32> = inner.into_iter().map(|p| mesh.add_vertex(p)).collect();
+
+            for i in 0..segments {
+                let next = (i + 1) % outer.len();
+
+                mesh.add_triangle(outer[i], outer[next], inner[next]);
+                mesh.add_triangle(outer[i], inner[next], inner[i]);
+            }
+        } else {
+            let center = mesh.add_vertex([0.0, 0.0]);
+
+            for i in 0..segments {
+                mesh.add_triangle(center, outer[i], outer[(i + 1) % outer.len()]);
+            }
+        }
+
+        mesh
+    }
+
+    pub(crate) fn to_stroke_mesh(&self) -> Mesh<StrokeVertex> {
+        let (outer, mut inner, full) = self.edges();
+        inner.reverse();
+
+        match (full, self.has_hole()) {
+            (true, true) => {
+                let mut mesh = stroke_mesh(&outer);
+                mesh.append(stroke_mesh(&inner));
+                mesh
+            }
+            (true, false) => stroke_mesh(&outer),
+            (false, true) => stroke_mesh(&[outer, inner].concat()),
+            (false, false) => stroke_mesh(&[outer, vec![[0.0, 0.0]]].concat()),
+        }
+    }
+}
+
+/// Shape which can be render and created which though the [arc](shape_renderer::ShapeRenderer::arc)
+///
+/// band along the outline of an oval between two angles
+pub struct Arc {
+    pub(crate) data: BasicShapeData,
+    pub(crate) geometry: ArcGeometry,
+}
+
+impl BasicShape for Arc {
+    fn data(&self) -> &BasicShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut BasicShapeData {
+        &mut self.data
+    }
+}
+
+impl Arc {
+    /// start and end of the arc in radians
+    ///
+    /// 0 -> right, goes counter clockwise from the start to the end
+    pub fn angles(&mut self, start_angle: f32, end_angle: f32) -> &mut Self {
+        self.geometry.start_angle = start_angle;
+        self.geometry.end_angle = end_angle;
+        self
+    }
+
+    /// radius of the inner edge relative to the outer one
+    ///
+    /// 0.0 -> center, 1.0 -> outline
+    pub fn inner_radius(&mut self, inner_radius: f32) -> &mut Self {
+        self.geometry.inner_radius = inner_radius;
+        self
+    }
+
+    ///segment count of a full oval
+    ///
+    /// higher -> smoother curve
+    pub fn segment_count(&mut self, segment_count: u32) -> &mut Self {
+        self.geometry.detail = segment_count;
+        self
+    }
+}
+
+impl Default for Arc {
+    fn default() -> Self {
+        Arc {
+            data: Default::default(),
+            geometry: ArcGeometry {
+                start_angle: 0.0,
+                end_angle: PI,
+                inner_radius: 0.5,
+                detail: 128,
+            },
+        }
+    }
+}
+
+/// Shape which can be render and created which though the [pie](shape_renderer::ShapeRenderer::pie)
+///
+/// slice of an oval between two angles
+pub struct Pie {
+    pub(crate) data: BasicShapeData,
+    pub(crate) geometry: ArcGeometry,
+}
+
+impl BasicShape for Pie {
+    fn data(&self) -> &BasicShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut BasicShapeData {
+        &mut self.data
+    }
+}
+
+impl Pie {
+    /// start and end of the slice in radians
+    ///
+    /// 0 -> right, goes counter clockwise from the start to the end
+    pub fn angles(&mut self, start_angle: f32, end_angle: f32) -> &mut Self {
+        self.geometry.start_angle = start_angle;
+        self.geometry.end_angle = end_angle;
+        self
+    }
+
+    ///segment count of a full oval
+    ///
+    /// higher -> smoother curve
+    pub fn segment_count(&mut self, segment_count: u32) -> &mut Self {
+        self.geometry.detail = segment_count;
+        self
+    }
+}
+
+impl Default for Pie {
+    fn default() -> Self {
+        Pie {
+            data: Default::default(),
+            geometry: ArcGeometry {
+                start_angle: 0.0,
+                end_angle: PI / 2.0,
+                inner_radius: 0.0,
+                detail: 128,
+            },
+        }
+    }
+}
+
+/// Shape which can be render and created which though the [ring](shape_renderer::ShapeRenderer::ring)
+///
+/// oval with an oval hole in the center
+pub struct Ring {
+    pub(crate) data: BasicShapeData,
+    pub(crate) geometry: ArcGeometry,
+}
+
+impl BasicShape for Ring {
+    fn data(&self) -> &BasicShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut BasicShapeData {
+        &mut self.data
+    }
+}
+
+impl Ring {
+    /// radius of the hole relative to the outer radius
+    ///
+    /// 0.0 -> no hole, 1.0 -> invisible
+    pub fn inner_radius(&mut self, inner_radius: f32) -> &mut Self {
+        self.geometry.inner_radius = inner_radius;
+        self
+    }
+
+    ///segment count of the ring
+    ///
+    /// higher -> smoother circle
+    pub fn segment_count(&mut self, segment_count: u32) -> &mut Self {
+        self.geometry.detail = segment_count;
+        self
+    }
+}
+
+impl Default for Ring {
+    fn default() -> Self {
+        Ring {
+            data: Default::default(),
+            geometry: ArcGeometry {
+                start_angle: 0.0,
+                end_angle: PI * 2.0,
+                inner_radius: 0.5,
+                detail: 128,
+            },
+        }
+    }
+}
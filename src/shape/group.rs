@@ -0,0 +1,60 @@
+use crate::transform::{from_parts, Affine};
+
+/// moves, rotates and scales every shape and group inside of it
+///
+/// created with [add_group](crate::shape_renderer::ShapeRenderer::add_group),
+/// shapes get added with [set_parent](crate::shape_renderer::ShapeRenderer::set_parent)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    pub(crate) pos: (f32, f32),
+    pub(crate) rotation: f32,
+    pub(crate) scale: (f32, f32),
+    pub(crate) layer: u16,
+}
+
+impl Group {
+    /// location of the origin of the group in its parent
+    pub fn pos(&mut self, x: f32, y: f32) -> &mut Self {
+        self.pos = (x, y);
+        self
+    }
+
+    /// rotation of the group around its origin in radians
+    pub fn rotation(&mut self, rotation: f32) -> &mut Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// factor the shapes inside get scaled by
+    ///
+    /// (1,1) -> original size, a non uniform scale skews rotated shapes inside
+    pub fn scale(&mut self, x: f32, y: f32) -> &mut Self {
+        self.scale = (x, y);
+        self
+    }
+
+    /// gets added to the layer of every shape inside
+    pub fn layer(&mut self, layer: u16) -> &mut Self {
+        self.layer = layer;
+        self
+    }
+
+    pub(crate) fn matrix(&self) -> Affine {
+        from_parts(
+            [self.pos.0, self.pos.1],
+            self.rotation,
+            [self.scale.0, self.scale.1],
+        )
+    }
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Group {
+            pos: (0.0, 0.0),
+            rotation: 0.0,
+            scale: (1.0, 1.0),
+            layer: 0,
+        }
+    }
+}
@@ -1,12 +1,20 @@
+use crate::render::atlas::{TextureAtlas, TextureId};
 use crate::render::instance::TextureInstance;
+use crate::shape::shapes::shape_matrix;
+use crate::transform::{Affine, IDENTITY};
 
+#[derive(Clone)]
 pub struct Image {
     pub(crate) scale: (f32, f32),
     pub(crate) pos: (f32, f32),
     pub(crate) rotation: f32,
     pub(crate) layer: u16,
-    pub(crate) texture_pos: (f32, f32),
-    pub(crate) texture_scale: (f32, f32),
+    /// its cords get looked up when it is drawn, they change when the atlas gets packed again
+    pub(crate) texture: Option<TextureId>,
+    pub(crate) alpha: f32,
+    pub(crate) screen_space: bool,
+    pub(crate) anchor: (f32, f32),
+    pub(crate) transform: Affine,
 }
 
 impl Image {
@@ -16,18 +24,26 @@ impl Image {
         self
     }
 
-    /// location of the shape in the frame
+    /// location of the [anchor](Image::anchor) of the shape in the frame
     pub fn pos(&mut self, x: f32, y: f32) -> &mut Self {
         self.pos = (x, y);
         self
     }
 
-    /// rotation of the shape in radians
+    /// rotation of the shape around its [anchor](Image::anchor) in radians
     pub fn rotation(&mut self, rotation: f32) -> &mut Self {
         self.rotation = rotation;
         self
     }
 
+    /// point of the image which is at the [pos](Image::pos) and which it rotates around
+    ///
+    /// relative to the size of the image, (0,0) -> center, (-1,-1) -> bottom left, (1,1) -> top right
+    pub fn anchor(&mut self, x: f32, y: f32) -> &mut Self {
+        self.anchor = (x, y);
+        self
+    }
+
     /// render layer of the shape
     ///
     /// higher layer -> foreground
@@ -36,15 +52,55 @@ impl Image {
         self
     }
 
-    pub fn to_instance(&self) -> TextureInstance {
-        TextureInstance {
-            position: [self.pos.0, self.pos.1],
+    /// opacity of the shape
+    ///
+    /// 1.0 -> opaque, 0.0 -> invisible
+    pub fn alpha(&mut self, alpha: f32) -> &mut Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// if the image is positioned in pixels on the screen instead of in the world
+    ///
+    /// (0,0) -> center of the screen
+    pub fn screen_space(&mut self, screen_space: bool) -> &mut Self {
+        self.screen_space = screen_space;
+        self
+    }
+
+    /// affine matrix which gets applied after the [pos](Image::pos), [rotation](Image::rotation) and [scale](Image::scale)
+    ///
+    /// rows of a 2x3 matrix, x' = m[0][0] * x + m[0][1] * y + m[0][2]
+    pub fn transform(&mut self, matrix: [[f32; 3]; 2]) -> &mut Self {
+        self.transform = matrix;
+        self
+    }
+
+    /// scaled image -> frame
+    pub(crate) fn matrix(&self) -> Affine {
+        shape_matrix(
+            self.pos,
+            self.rotation,
+            self.scale,
+            self.anchor,
+            &self.transform,
+        )
+    }
+
+    /// None if the texture got removed
+    pub(crate) fn to_instance(&self, atlas: &TextureAtlas) -> Option<TextureInstance> {
+        let (texture_pos, texture_scale, texture_page) = atlas.cords(self.texture?)?;
+
+        Some(TextureInstance {
+            transform: self.matrix(),
             scale: [self.scale.0, self.scale.1],
-            rotation: self.rotation,
             layer: self.layer as u32,
-            texture_position: [self.texture_pos.0, self.texture_pos.1],
-            texture_scale: [self.texture_scale.0, self.texture_scale.1],
-        }
+            texture_position: [texture_pos.0, texture_pos.1],
+            texture_scale: [texture_scale.0, texture_scale.1],
+            texture_page,
+            alpha: self.alpha,
+            screen_space: self.screen_space as u32,
+        })
     }
 }
 
@@ -55,8 +111,11 @@ impl Default for Image {
             pos: (0.0, 0.0),
             rotation: 0.0,
             layer: 0,
-            texture_pos: (0.0, 0.0),
-            texture_scale: (1.0, 1.0),
+            texture: None,
+            alpha: 1.0,
+            screen_space: false,
+            anchor: (0.0, 0.0),
+            transform: IDENTITY,
         }
     }
 }
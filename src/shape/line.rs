@@ -0,0 +1,363 @@
+use std::f32::consts::PI;
+
+use wgpu::Color;
+
+use crate::math::{add, angle, cross, dot, length, normalize, perpendicular, polar, scale, sub};
+use crate::render::instance::{Instance, SdfInstance, SdfKind};
+use crate::render::mesh::Mesh;
+use crate::shape::shapes::shape_matrix;
+use crate::transform::{from_parts, multiply, Affine, IDENTITY};
+
+/// miter joins which would be longer than this times the thickness become bevel joins
+const MITER_LIMIT: f32 = 4.0;
+
+/// how the segments of a [Line] get connected
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineJoin {
+    /// the outer edges get extended until they meet
+    Miter,
+    /// the outer corner is rounded
+    Round,
+    /// the outer corner is cut off
+    Bevel,
+}
+
+/// how the ends of an open [Line] look like
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    /// the line ends exactly at its end points
+    Butt,
+    /// the line ends with a half circle around its end points
+    Round,
+    /// the line gets extended by half its thickness
+    Square,
+}
+
+/// Line which can be render and created which though the [line](shape_renderer::ShapeRenderer::line)
+/// or [polyline](shape_renderer::ShapeRenderer::polyline)
+pub struct Line {
+    pub(crate) points: Vec<(f32, f32)>,
+    pub(crate) thickness: f32,
+    pub(crate) color: (f32, f32, f32, f32),
+    pub(crate) layer: u16,
+    pub(crate) screen_space: bool,
+    pub(crate) join: LineJoin,
+    pub(crate) cap: LineCap,
+    pub(crate) closed: bool,
+    pub(crate) anchor: (f32, f32),
+    pub(crate) transform: Affine,
+}
+
+impl Line {
+    pub(crate) fn new(points: Vec<(f32, f32)>) -> Self {
+        Line {
+            points,
+            thickness: 2.0,
+            color: (0.0, 0.0, 1.0, 1.0),
+            layer: 0,
+            screen_space: false,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            closed: false,
+            anchor: (0.0, 0.0),
+            transform: IDENTITY,
+        }
+    }
+
+    /// width of the line in frame units
+    pub fn thickness(&mut self, thickness: f32) -> &mut Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// color of the line
+    pub fn color(&mut self, red: f32, green: f32, blue: f32) -> &mut Self {
+        self.color = (red, green, blue, self.color.3);
+        self
+    }
+
+    /// color of the line including its alpha
+    pub fn color_from_color(&mut self, color: Color) -> &mut Self {
+        self.color = (
+            color.r as f32,
+            color.g as f32,
+            color.b as f32,
+            color.a as f32,
+        );
+        self
+    }
+
+    /// opacity of the line
+    ///
+    /// 1.0 -> opaque, 0.0 -> invisible
+    pub fn alpha(&mut self, alpha: f32) -> &mut Self {
+        self.color.3 = alpha;
+        self
+    }
+
+    /// render layer of the line
+    ///
+    /// higher layer -> foreground
+    pub fn layer(&mut self, layer: u16) -> &mut Self {
+        self.layer = layer;
+        self
+    }
+
+    /// if the points are in pixels on the screen instead of in the world
+    pub fn screen_space(&mut self, screen_space: bool) -> &mut Self {
+        self.screen_space = screen_space;
+        self
+    }
+
+    /// how the segments get connected
+    pub fn join(&mut self, join: LineJoin) -> &mut Self {
+        self.join = join;
+        self
+    }
+
+    /// how the ends of the line look like
+    ///
+    /// has no effect on closed lines
+    pub fn cap(&mut self, cap: LineCap) -> &mut Self {
+        self.cap = cap;
+        self
+    }
+
+    /// if the last point gets connected back to the first one
+    pub fn closed(&mut self, closed: bool) -> &mut Self {
+        self.closed = closed;
+        self
+    }
+
+    /// point in frame units which gets moved to (0,0) before the [transform](Line::transform) is applied
+    ///
+    /// the transform rotates and scales the line around it
+    pub fn anchor(&mut self, x: f32, y: f32) -> &mut Self {
+        self.anchor = (x, y);
+        self
+    }
+
+    /// affine matrix which gets applied to the points
+    ///
+    /// rows of a 2x3 matrix, x' = m[0][0] * x + m[0][1] * y + m[0][2].
+    /// allows skew and reflection, the thickness gets transformed together with the line
+    pub fn transform(&mut self, matrix: [[f32; 3]; 2]) -> &mut Self {
+        self.transform = matrix;
+        self
+    }
+
+    /// points of the mesh -> frame
+    ///
+    /// the mesh is drawn with a scale of 2, so the anchor is in frame units like the points
+    pub(crate) fn matrix(&self) -> Affine {
+        shape_matrix((0.0, 0.0), 0.0, (2.0, 2.0), self.anchor, &self.transform)
+    }
+
+    /// the points of the mesh are already in frame units
+    pub fn to_instance(&self) -> Instance {
+        Instance {
+            transform: self.matrix(),
+            scale: [2.0, 2.0],
+            color: [self.color.0, self.color.1, self.color.2, self.color.3],
+            layer: self.layer as u32,
+            screen_space: self.screen_space as u32,
+        }
+    }
+
+    /// straight lines get drawn as a rect which is rounded for round caps
+    ///
+    /// lines with multiple segments need their joins and have no distance instance
+    pub(crate) fn to_sdf_instance(&self) -> Option<SdfInstance> {
+        let [from, to] = self.points[..] else {
+            return None;
+        };
+
+        let (from, to) = ([from.0, from.1], [to.0, to.1]);
+        let direction = sub(to, from);
+        let half = self.thickness / 2.0;
+
+        if self.closed || length(direction) < f32::EPSILON || half <= 0.0 {
+            return None;
+        }
+
+        let (extension, radius) = match self.cap {
+            LineCap::Butt => (0.0, 0.0),
+            LineCap::Round => (self.thickness, half),
+            LineCap::Square => (self.thickness, 0.0),
+        };
+
+        let center = scale(add(from, to), 0.5);
+
+        Some(SdfInstance {
+            transform: multiply(
+                &self.matrix(),
+                &from_parts(center, angle(direction), [1.0, 1.0]),
+            ),
+            scale: [length(direction) + extension, self.thickness],
+            color: [self.color.0, self.color.1, self.color.2, self.color.3],
+            layer: self.layer as u32,
+            kind: SdfKind::Box as u32,
+            corner_radii: [radius; 4],
+            stroke_width: 0.0,
+            stroke_color: [0.0; 4],
+            screen_space: self.screen_space as u32,
+        })
+    }
+
+    pub(crate) fn to_mesh(&self) -> Mesh {
+        let points: Vec<[f32; 2]> = self.points.iter().map(|(x, y)| [*x, *y]).collect();
+        tessellate_line(&points, self.thickness, self.join, self.cap, self.closed)
+    }
+}
+
+/// creates the triangles of a line along the given points
+pub(crate) fn tessellate_line(
+    points: &[[f32; 2]],
+    thickness: f32,
+    join: LineJoin,
+    cap: LineCap,
+    closed: bool,
+) -> Mesh {
+    let mut mesh = Mesh::default();
+
+    let mut points: Vec<[f32; 2]> = points.to_vec();
+    points.dedup_by(|a, b| length(sub(*a, *b)) < f32::EPSILON);
+    if closed && points.len() > 2 && length(sub(points[0], points[points.len() - 1])) < f32::EPSILON
+    {
+        points.pop();
+    }
+
+    if points.len() < 2 || thickness <= 0.0 {
+        return mesh;
+    }
+
+    let half = thickness / 2.0;
+    let count = points.len();
+    let closed = closed && count > 2;
+    let segment_count = if closed { count } else { count - 1 };
+
+    // left and right edge of the start and the end of every segment
+    let mut starts = vec![];
+    let mut ends = vec![];
+
+    for i in 0..segment_count {
+        let mut start = points[i];
+        let mut end = points[(i + 1) % count];
+
+        let direction = normalize(sub(end, start));
+        let normal = scale(perpendicular(direction), half);
+
+        if !closed && cap == LineCap::Square {
+            if i == 0 {
+                start = sub(start, scale(direction, half));
+            }
+            if i == segment_count - 1 {
+                end = add(end, scale(direction, half));
+            }
+        }
+
+        starts.push((add(start, normal), sub(start, normal)));
+        ends.push((add(end, normal), sub(end, normal)));
+    }
+
+    let joints = if closed { 0..count } else { 1..count - 1 };
+
+    for i in joints {
+        let point = points[i];
+        let previous = points[(i + count - 1) % count];
+        let next = points[(i + 1) % count];
+
+        let before = normalize(sub(point, previous));
+        let after = normalize(sub(next, point));
+
+        let turn = cross(before, after);
+        if turn.abs() < f32::EPSILON && dot(before, after) > 0.0 {
+            continue;
+        }
+
+        // the join is only needed on the outer side of the turn
+        let outer_left = turn < 0.0;
+        let side = if outer_left { 1.0 } else { -1.0 };
+        let before_normal = scale(perpendicular(before), side);
+        let after_normal = scale(perpendicular(after), side);
+
+        let outer_before = add(point, scale(before_normal, half));
+        let outer_after = add(point, scale(after_normal, half));
+
+        let divisor = 1.0 + dot(before_normal, after_normal);
+        let miter = scale(
+            add(before_normal, after_normal),
+            1.0 / divisor.max(f32::EPSILON),
+        );
+
+        // both segments end in the same inner point so they do not overlap
+        let inner_length = length(miter) * half;
+        if divisor > f32::EPSILON
+            && inner_length <= length(sub(point, previous))
+            && inner_length <= length(sub(next, point))
+        {
+            let inner = sub(point, scale(miter, half));
+            let before_segment = (i + segment_count - 1) % segment_count;
+
+            if outer_left {
+                ends[before_segment].1 = inner;
+                starts[i].1 = inner;
+            } else {
+                ends[before_segment].0 = inner;
+                starts[i].0 = inner;
+            }
+
+            mesh.add_triangle_points(inner, outer_before, point);
+            mesh.add_triangle_points(inner, point, outer_after);
+        }
+
+        match join {
+            LineJoin::Miter if divisor > f32::EPSILON && length(miter) <= MITER_LIMIT => {
+                let tip = add(point, scale(miter, half));
+                mesh.add_quad_points(point, outer_before, tip, outer_after);
+            }
+            LineJoin::Round => {
+                let sweep =
+                    cross(before_normal, after_normal).atan2(dot(before_normal, after_normal));
+                add_arc(&mut mesh, point, half, angle(before_normal), sweep);
+            }
+            _ => mesh.add_triangle_points(point, outer_before, outer_after),
+        }
+    }
+
+    for ((start_left, start_right), (end_left, end_right)) in starts.into_iter().zip(ends) {
+        mesh.add_quad_points(start_left, end_left, end_right, start_right);
+    }
+
+    if !closed && cap == LineCap::Round {
+        let start_normal = perpendicular(normalize(sub(points[1], points[0])));
+        add_arc(&mut mesh, points[0], half, angle(start_normal), PI);
+
+        let end_normal = perpendicular(normalize(sub(points[count - 1], points[count - 2])));
+        add_arc(
+            &mut mesh,
+            points[count - 1],
+            half,
+            angle(end_normal) + PI,
+            PI,
+        );
+    }
+
+    mesh
+}
+
+/// adds a triangle fan around the center from start_angle rotated by sweep
+fn add_arc(mesh: &mut Mesh, center: [f32; 2], radius: f32, start_angle: f32, sweep: f32) {
+    let segments = (sweep.abs() / (PI / 16.0)).ceil().max(1.0) as u32;
+
+    let center_index = mesh.add_vertex(center);
+    let mut previous = mesh.add_vertex(add(center, polar(start_angle, radius)));
+
+    for i in 1..=segments {
+        let angle = start_angle + sweep * i as f32 / segments as f32;
+        let current = mesh.add_vertex(add(center, polar(angle, radius)));
+
+        mesh.add_triangle(center_index, previous, current);
+        previous = current;
+    }
+}
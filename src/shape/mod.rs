@@ -1,4 +1,10 @@
+pub mod arc;
+pub mod group;
 pub mod image;
+pub mod line;
 pub mod oval;
+pub mod path;
+pub mod polygon;
 pub mod rect;
+pub mod rounded_rect;
 pub mod shapes;
@@ -1,47 +1,21 @@
-use wgpu::Color;
+use std::f32::consts::PI;
 
-use crate::render::instance::Instance;
 use crate::shape::shapes::{BasicShape, BasicShapeData};
 
 /// Shape which can be render and created which though the [oval](shape_renderer::ShapeRenderer::oval)
+#[derive(Clone)]
 pub struct Oval {
     pub(crate) data: BasicShapeData,
     pub(crate) detail: u32,
 }
 
 impl BasicShape for Oval {
-    fn scale(&mut self, width: f32, height: f32) -> &mut Self {
-        self.data.scale = (width, height);
-        self
+    fn data(&self) -> &BasicShapeData {
+        &self.data
     }
 
-    fn pos(&mut self, x: f32, y: f32) -> &mut Self {
-        self.data.pos = (x, y);
-        self
-    }
-
-    fn rotation(&mut self, rotation: f32) -> &mut Self {
-        self.data.rotation = rotation;
-        self
-    }
-
-    fn color(&mut self, red: f32, green: f32, blue: f32) -> &mut Self {
-        self.data.color = (red, green, blue);
-        self
-    }
-
-    fn color_from_color(&mut self, color: Color) -> &mut Self {
-        self.data.color = (color.r as f32, color.g as f32, color.b as f32);
-        self
-    }
-
-    fn layer(&mut self, layer: u16) -> &mut Self {
-        self.data.layer = layer;
-        self
-    }
-
-    fn to_instance(&self) -> Instance {
-        (&self.data).into()
+    fn data_mut(&mut self) -> &mut BasicShapeData {
+        &mut self.data
     }
 }
 
@@ -63,3 +37,67 @@ impl Default for Oval {
         }
     }
 }
+
+/// counter clockwise points of an oval with the given segment count
+///
+/// (-1,-1) -> bottom left, (1,1) -> top right
+pub(crate) fn oval_outline(detail: u32) -> Vec<[f32; 2]> {
+    let mut outline = oval_points(detail, 0.0, PI * 2.0);
+    outline.pop();
+    outline
+}
+
+/// triangle fan over an [oval_outline] with the given number of points
+pub(crate) fn oval_indices(vertex_count: usize) -> Vec<i32> {
+    (0..(vertex_count as i32 - 2))
+        .flat_map(|i| [0, i + 1, i + 2])
+        .collect()
+}
+
+/// points of the part of an oval going from start_angle over sweep_angle, including both ends
+///
+/// the angles are in radians, uses as many segments as the part would have in an oval with the given segment count
+pub(crate) fn oval_points(detail: u32, start_angle: f32, sweep_angle: f32) -> Vec<[f32; 2]> {
+    let sweep_angle = sweep_angle.clamp(-PI * 2.0, PI * 2.0);
+    let detail = detail.max(3);
+
+    // a full turn would round up to an extra segment for some counts
+    let count = if sweep_angle.abs() >= PI * 2.0 {
+        detail
+    } else {
+        (detail as f32 * sweep_angle.abs() / (PI * 2.0) - 1e-3)
+            .ceil()
+            .max(1.0) as u32
+    };
+
+    (0..=count)
+        .map(|i| {
+            let angle = start_angle + sweep_angle / count as f32 * i as f32;
+
+            [angle.cos(), angle.sin()]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outline_has_one_point_per_segment() {
+        for detail in 3..=256 {
+            assert_eq!(oval_outline(detail).len(), detail as usize, "{}", detail);
+        }
+    }
+
+    #[test]
+    fn fan_covers_the_outline() {
+        for detail in 0..=16 {
+            let outline = oval_outline(detail);
+            let indices = oval_indices(outline.len());
+
+            assert_eq!(indices.len(), (outline.len() - 2) * 3);
+            assert_eq!(*indices.iter().max().unwrap(), outline.len() as i32 - 1);
+        }
+    }
+}
@@ -0,0 +1,393 @@
+use wgpu::Color;
+
+use crate::math::{add, angle, length, polar, scale, sub};
+use crate::render::instance::Instance;
+use crate::render::mesh::Mesh;
+use crate::render::triangulation::{signed_area, triangulate};
+use crate::shape::line::{tessellate_line, LineCap, LineJoin};
+use crate::shape::shapes::shape_matrix;
+use crate::transform::{Affine, IDENTITY};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum PathEvent {
+    MoveTo([f32; 2]),
+    LineTo([f32; 2]),
+    QuadTo([f32; 2], [f32; 2]),
+    CubicTo([f32; 2], [f32; 2], [f32; 2]),
+    ArcTo([f32; 2], f32),
+    Close,
+}
+
+/// vector path made out of lines and curves which can be rendered with
+/// [fill_path](shape_renderer::ShapeRenderer::fill_path) or [stroke_path](shape_renderer::ShapeRenderer::stroke_path)
+///
+/// created with a [PathBuilder]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Path {
+    events: Vec<PathEvent>,
+}
+
+/// helps to create a [Path]
+///
+/// all points are in frame units, a path starts at (0,0) until [move_to](PathBuilder::move_to) is called
+#[derive(Default)]
+pub struct PathBuilder {
+    events: Vec<PathEvent>,
+}
+
+impl PathBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// starts a new sub path at the point
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        self.events.push(PathEvent::MoveTo([x, y]));
+        self
+    }
+
+    /// straight line to the point
+    pub fn line_to(mut self, x: f32, y: f32) -> Self {
+        self.events.push(PathEvent::LineTo([x, y]));
+        self
+    }
+
+    /// quadratic bézier curve to the point
+    pub fn quad_to(mut self, control: (f32, f32), to: (f32, f32)) -> Self {
+        self.events
+            .push(PathEvent::QuadTo([control.0, control.1], [to.0, to.1]));
+        self
+    }
+
+    /// cubic bézier curve to the point
+    pub fn cubic_to(
+        mut self,
+        control_1: (f32, f32),
+        control_2: (f32, f32),
+        to: (f32, f32),
+    ) -> Self {
+        self.events.push(PathEvent::CubicTo(
+            [control_1.0, control_1.1],
+            [control_2.0, control_2.1],
+            [to.0, to.1],
+        ));
+        self
+    }
+
+    /// circular arc around the center starting at the current point
+    ///
+    /// sweep_angle in radians, positive -> counter clockwise
+    pub fn arc_to(mut self, center: (f32, f32), sweep_angle: f32) -> Self {
+        self.events
+            .push(PathEvent::ArcTo([center.0, center.1], sweep_angle));
+        self
+    }
+
+    /// connects the current point back to the start of the sub path
+    pub fn close(mut self) -> Self {
+        self.events.push(PathEvent::Close);
+        self
+    }
+
+    pub fn build(self) -> Path {
+        Path {
+            events: self.events,
+        }
+    }
+}
+
+impl Path {
+    /// turns every sub path into points which are at most tolerance away from the curves
+    ///
+    /// returns the points of every sub path and if it got closed
+    pub(crate) fn flatten(&self, tolerance: f32) -> Vec<(Vec<[f32; 2]>, bool)> {
+        let tolerance = tolerance.max(f32::EPSILON);
+
+        let mut sub_paths = vec![];
+        let mut current: Vec<[f32; 2]> = vec![[0.0, 0.0]];
+
+        for event in &self.events {
+            let from = *current.last().unwrap();
+
+            match *event {
+                PathEvent::MoveTo(to) => {
+                    if current.len() > 1 {
+                        sub_paths.push((current, false));
+                    }
+                    current = vec![to];
+                }
+                PathEvent::LineTo(to) => current.push(to),
+                PathEvent::QuadTo(control, to) => {
+                    let deviation = length(add(sub(from, scale(control, 2.0)), to));
+                    let count = (deviation / (4.0 * tolerance)).sqrt().ceil().max(1.0) as u32;
+
+                    current.extend((1..=count).map(|i| {
+                        let t = i as f32 / count as f32;
+                        let u = 1.0 - t;
+
+                        add(
+                            add(scale(from, u * u), scale(control, 2.0 * u * t)),
+                            scale(to, t * t),
+                        )
+                    }));
+                }
+                PathEvent::CubicTo(control_1, control_2, to) => {
+                    let deviation = length(add(sub(from, scale(control_1, 2.0)), control_2))
+                        .max(length(add(sub(control_1, scale(control_2, 2.0)), to)));
+                    let count = (3.0 * deviation / (4.0 * tolerance)).sqrt().ceil().max(1.0) as u32;
+
+                    current.extend((1..=count).map(|i| {
+                        let t = i as f32 / count as f32;
+                        let u = 1.0 - t;
+
+                        add(
+                            add(scale(from, u * u * u), scale(control_1, 3.0 * u * u * t)),
+                            add(scale(control_2, 3.0 * u * t * t), scale(to, t * t * t)),
+                        )
+                    }));
+                }
+                PathEvent::ArcTo(center, sweep) => {
+                    let radius = length(sub(from, center));
+                    let start = angle(sub(from, center));
+
+                    // largest step where the chord stays within the tolerance
+                    let step = 2.0 * (1.0 - (tolerance / radius).min(1.0)).acos();
+                    let count = (sweep.abs() / step.max(f32::EPSILON))
+                        .ceil()
+                        .clamp(1.0, 1024.0) as u32;
+
+                    current.extend((1..=count).map(|i| {
+                        add(
+                            center,
+                            polar(start + sweep * i as f32 / count as f32, radius),
+                        )
+                    }));
+                }
+                PathEvent::Close => {
+                    let start = current[0];
+                    if current.len() > 1 {
+                        sub_paths.push((current, true));
+                    }
+                    current = vec![start];
+                }
+            }
+        }
+
+        if current.len() > 1 {
+            sub_paths.push((current, false));
+        }
+
+        sub_paths
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub(crate) enum PathStyle {
+    Fill,
+    Stroke,
+}
+
+/// [Path] which can be render and created which though the [fill_path](shape_renderer::ShapeRenderer::fill_path)
+/// or [stroke_path](shape_renderer::ShapeRenderer::stroke_path)
+pub struct PathShape {
+    pub(crate) path: Path,
+    pub(crate) style: PathStyle,
+    pub(crate) color: (f32, f32, f32, f32),
+    pub(crate) layer: u16,
+    pub(crate) screen_space: bool,
+    pub(crate) thickness: f32,
+    pub(crate) join: LineJoin,
+    pub(crate) cap: LineCap,
+    pub(crate) anchor: (f32, f32),
+    pub(crate) transform: Affine,
+}
+
+impl PathShape {
+    pub(crate) fn new(path: Path, style: PathStyle) -> Self {
+        PathShape {
+            path,
+            style,
+            color: (0.0, 0.0, 1.0, 1.0),
+            layer: 0,
+            screen_space: false,
+            thickness: 2.0,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            anchor: (0.0, 0.0),
+            transform: IDENTITY,
+        }
+    }
+
+    /// color of the path
+    pub fn color(&mut self, red: f32, green: f32, blue: f32) -> &mut Self {
+        self.color = (red, green, blue, self.color.3);
+        self
+    }
+
+    /// color of the path including its alpha
+    pub fn color_from_color(&mut self, color: Color) -> &mut Self {
+        self.color = (
+            color.r as f32,
+            color.g as f32,
+            color.b as f32,
+            color.a as f32,
+        );
+        self
+    }
+
+    /// opacity of the path
+    ///
+    /// 1.0 -> opaque, 0.0 -> invisible
+    pub fn alpha(&mut self, alpha: f32) -> &mut Self {
+        self.color.3 = alpha;
+        self
+    }
+
+    /// render layer of the path
+    ///
+    /// higher layer -> foreground
+    pub fn layer(&mut self, layer: u16) -> &mut Self {
+        self.layer = layer;
+        self
+    }
+
+    /// if the points are in pixels on the screen instead of in the world
+    pub fn screen_space(&mut self, screen_space: bool) -> &mut Self {
+        self.screen_space = screen_space;
+        self
+    }
+
+    /// width of the stroke in frame units
+    ///
+    /// only used by stroked paths
+    pub fn thickness(&mut self, thickness: f32) -> &mut Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// how the segments of a stroked path get connected
+    pub fn join(&mut self, join: LineJoin) -> &mut Self {
+        self.join = join;
+        self
+    }
+
+    /// how the ends of open sub paths of a stroked path look like
+    pub fn cap(&mut self, cap: LineCap) -> &mut Self {
+        self.cap = cap;
+        self
+    }
+
+    /// point in frame units which gets moved to (0,0) before the [transform](PathShape::transform) is applied
+    ///
+    /// the transform rotates and scales the path around it
+    pub fn anchor(&mut self, x: f32, y: f32) -> &mut Self {
+        self.anchor = (x, y);
+        self
+    }
+
+    /// affine matrix which gets applied to the points
+    ///
+    /// rows of a 2x3 matrix, x' = m[0][0] * x + m[0][1] * y + m[0][2].
+    /// allows skew and reflection, the stroke gets transformed together with the path
+    pub fn transform(&mut self, matrix: [[f32; 3]; 2]) -> &mut Self {
+        self.transform = matrix;
+        self
+    }
+
+    /// points of the mesh -> frame
+    ///
+    /// the mesh is drawn with a scale of 2, so the anchor is in frame units like the points
+    pub(crate) fn matrix(&self) -> Affine {
+        shape_matrix((0.0, 0.0), 0.0, (2.0, 2.0), self.anchor, &self.transform)
+    }
+
+    /// the points of the mesh are already in frame units
+    pub fn to_instance(&self) -> Instance {
+        Instance {
+            transform: self.matrix(),
+            scale: [2.0, 2.0],
+            color: [self.color.0, self.color.1, self.color.2, self.color.3],
+            layer: self.layer as u32,
+            screen_space: self.screen_space as u32,
+        }
+    }
+
+    /// tolerance is the max distance in frame units between the curves and the generated mesh
+    pub(crate) fn to_mesh(&self, tolerance: f32) -> Mesh {
+        let sub_paths = self.path.flatten(tolerance);
+        let mut mesh = Mesh::default();
+
+        match self.style {
+            PathStyle::Stroke => {
+                for (points, closed) in sub_paths {
+                    mesh.append(tessellate_line(
+                        &points,
+                        self.thickness,
+                        self.join,
+                        self.cap,
+                        closed,
+                    ));
+                }
+            }
+            PathStyle::Fill => {
+                let rings: Vec<_> = sub_paths
+                    .into_iter()
+                    .map(|(points, _)| points)
+                    .filter(|points| points.len() >= 3)
+                    .collect();
+
+                // sub paths inside an odd number of other sub paths are holes
+                let parents: Vec<Option<usize>> = (0..rings.len())
+                    .map(|i| {
+                        let containing: Vec<usize> = (0..rings.len())
+                            .filter(|j| *j != i && contains(&rings[*j], rings[i][0]))
+                            .collect();
+
+                        if containing.len() % 2 == 0 {
+                            return None;
+                        }
+
+                        containing.into_iter().min_by(|a, b| {
+                            signed_area(&rings[*a])
+                                .abs()
+                                .total_cmp(&signed_area(&rings[*b]).abs())
+                        })
+                    })
+                    .collect();
+
+                for (i, ring) in rings.iter().enumerate() {
+                    if parents[i].is_some() {
+                        continue;
+                    }
+
+                    let holes: Vec<_> = (0..rings.len())
+                        .filter(|j| parents[*j] == Some(i))
+                        .map(|j| rings[j].clone())
+                        .collect();
+
+                    mesh.append(triangulate(ring, &holes));
+                }
+            }
+        }
+
+        mesh
+    }
+}
+
+/// even odd test if the point is inside of the ring
+fn contains(ring: &[[f32; 2]], point: [f32; 2]) -> bool {
+    let mut inside = false;
+
+    for i in 0..ring.len() {
+        let a = ring[i];
+        let b = ring[(i + 1) % ring.len()];
+
+        if (a[1] > point[1]) != (b[1] > point[1])
+            && point[0] < a[0] + (point[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0])
+        {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
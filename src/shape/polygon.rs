@@ -0,0 +1,112 @@
+use crate::render::mesh::Mesh;
+use crate::render::stroke::{stroke_mesh, StrokeVertex};
+use crate::render::triangulation::{signed_area, triangulate};
+use crate::shape::shapes::{BasicShape, BasicShapeData};
+
+/// Shape which can be render and created which though the [polygon](shape_renderer::ShapeRenderer::polygon)
+///
+/// the outline can be concave and can contain holes but should not intersect itself
+pub struct Polygon {
+    pub(crate) data: BasicShapeData,
+    pub(crate) outline: Vec<(f32, f32)>,
+    pub(crate) holes: Vec<Vec<(f32, f32)>>,
+    /// center and size of the outline in the units the points were given in
+    bounds: ((f32, f32), (f32, f32)),
+}
+
+impl BasicShape for Polygon {
+    fn data(&self) -> &BasicShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut BasicShapeData {
+        &mut self.data
+    }
+}
+
+impl Polygon {
+    /// the [pos](BasicShape::pos) is the center and the [scale](BasicShape::scale) the size of the bounding box of the outline
+    pub(crate) fn new(outline: &[(f32, f32)]) -> Self {
+        let (min, max) = outline.iter().fold(
+            ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN)),
+            |(min, max), (x, y)| {
+                (
+                    (min.0.min(*x), min.1.min(*y)),
+                    (max.0.max(*x), max.1.max(*y)),
+                )
+            },
+        );
+
+        let center = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0);
+        let size = (max.0 - min.0, max.1 - min.1);
+
+        Polygon {
+            data: BasicShapeData {
+                pos: center,
+                scale: size,
+                ..Default::default()
+            },
+            outline: outline.to_vec(),
+            holes: vec![],
+            bounds: (center, size),
+        }
+    }
+
+    /// cuts a hole out of the polygon
+    ///
+    /// the points are in the same units as the outline
+    pub fn hole(&mut self, hole: &[(f32, f32)]) -> &mut Self {
+        self.holes.push(hole.to_vec());
+        self
+    }
+
+    /// outline and holes relative to the bounding box
+    ///
+    /// (-1,-1) -> bottom left, (1,1) -> top right
+    fn unit_rings(&self) -> (Vec<[f32; 2]>, Vec<Vec<[f32; 2]>>) {
+        let ((center_x, center_y), (width, height)) = self.bounds;
+        let half_width = (width / 2.0).max(f32::EPSILON);
+        let half_height = (height / 2.0).max(f32::EPSILON);
+
+        let to_unit = |points: &Vec<(f32, f32)>| -> Vec<[f32; 2]> {
+            points
+                .iter()
+                .map(|(x, y)| [(x - center_x) / half_width, (y - center_y) / half_height])
+                .collect()
+        };
+
+        (
+            to_unit(&self.outline),
+            self.holes.iter().map(to_unit).collect(),
+        )
+    }
+
+    pub(crate) fn to_mesh(&self) -> Mesh {
+        let (outline, holes) = self.unit_rings();
+        triangulate(&outline, &holes)
+    }
+
+    /// strokes the outline and every hole on the side of the filled area
+    pub(crate) fn to_stroke_mesh(&self) -> Mesh<StrokeVertex> {
+        let (mut outline, holes) = self.unit_rings();
+        let mut mesh = Mesh::default();
+
+        if outline.len() < 3 {
+            return mesh;
+        }
+
+        if signed_area(&outline) < 0.0 {
+            outline.reverse();
+        }
+        mesh.append(stroke_mesh(&outline));
+
+        for mut hole in holes.into_iter().filter(|hole| hole.len() >= 3) {
+            if signed_area(&hole) > 0.0 {
+                hole.reverse();
+            }
+            mesh.append(stroke_mesh(&hole));
+        }
+
+        mesh
+    }
+}
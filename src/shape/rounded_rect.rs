@@ -0,0 +1,162 @@
+use std::f32::consts::PI;
+
+use crate::render::atlas::{TextureAtlas, TextureId};
+use crate::render::instance::TextureInstance;
+use crate::render::mesh::Mesh;
+use crate::render::stroke::{stroke_mesh, StrokeVertex};
+use crate::shape::shapes::{BasicShape, BasicShapeData};
+
+/// Shape which can be render and created which though the [rounded_rect](shape_renderer::ShapeRenderer::rounded_rect)
+/// or [rounded_image](shape_renderer::ShapeRenderer::rounded_image)
+pub struct RoundedRect {
+    pub(crate) data: BasicShapeData,
+    /// top left, top right, bottom right, bottom left
+    pub(crate) radii: [f32; 4],
+    pub(crate) detail: u32,
+    /// texture it is filled with, its cords get looked up when it is drawn
+    pub(crate) texture: Option<TextureId>,
+}
+
+impl BasicShape for RoundedRect {
+    fn data(&self) -> &BasicShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut BasicShapeData {
+        &mut self.data
+    }
+}
+
+impl RoundedRect {
+    /// radius of all corners in frame units
+    pub fn corner_radius(&mut self, radius: f32) -> &mut Self {
+        self.radii = [radius; 4];
+        self
+    }
+
+    /// radius of every corner in frame units
+    ///
+    /// radii which do not fit into the shape get reduced
+    pub fn corner_radii(
+        &mut self,
+        top_left: f32,
+        top_right: f32,
+        bottom_right: f32,
+        bottom_left: f32,
+    ) -> &mut Self {
+        self.radii = [top_left, top_right, bottom_right, bottom_left];
+        self
+    }
+
+    /// segment count of every corner
+    ///
+    /// higher -> smoother corners
+    pub fn segment_count(&mut self, segment_count: u32) -> &mut Self {
+        self.detail = segment_count;
+        self
+    }
+
+    /// None if it is not filled with a texture or the texture got removed
+    pub(crate) fn to_texture_instance(&self, atlas: &TextureAtlas) -> Option<TextureInstance> {
+        let (texture_pos, texture_scale, texture_page) = atlas.cords(self.texture?)?;
+
+        Some(TextureInstance {
+            transform: self.data.matrix(),
+            scale: [self.data.scale.0, self.data.scale.1],
+            layer: self.data.layer as u32,
+            texture_position: [texture_pos.0, texture_pos.1],
+            texture_scale: [texture_scale.0, texture_scale.1],
+            texture_page,
+            alpha: self.data.color.3,
+            screen_space: self.data.screen_space as u32,
+        })
+    }
+
+    /// counter clockwise outline relative to the size of the shape
+    ///
+    /// (-1,-1) -> bottom left, (1,1) -> top right
+    fn outline(&self) -> Vec<[f32; 2]> {
+        let half_width = (self.data.scale.0 / 2.0).abs().max(f32::EPSILON);
+        let half_height = (self.data.scale.1 / 2.0).abs().max(f32::EPSILON);
+        let max_radius = half_width.min(half_height);
+
+        let [top_left, top_right, bottom_right, bottom_left] = self.radii;
+
+        // corner position and radius, ordered counter clockwise starting at the bottom right
+        let corners = [
+            ([1.0, -1.0], bottom_right),
+            ([1.0, 1.0], top_right),
+            ([-1.0, 1.0], top_left),
+            ([-1.0, -1.0], bottom_left),
+        ];
+
+        let mut outline = vec![];
+
+        for (i, ([x, y], radius)) in corners.into_iter().enumerate() {
+            let radius = radius.clamp(0.0, max_radius);
+
+            if radius <= 0.0 {
+                outline.push([x, y]);
+                continue;
+            }
+
+            let center_x = x * (half_width - radius);
+            let center_y = y * (half_height - radius);
+            let start = -PI / 2.0 + PI / 2.0 * i as f32;
+            let detail = self.detail.max(1);
+
+            outline.extend((0..=detail).map(|step| {
+                let angle = start + PI / 2.0 * step as f32 / detail as f32;
+
+                [
+                    (center_x + angle.cos() * radius) / half_width,
+                    (center_y + angle.sin() * radius) / half_height,
+                ]
+            }));
+        }
+
+        outline.dedup_by(|a, b| (a[0] - b[0]).abs() < 1e-6 && (a[1] - b[1]).abs() < 1e-6);
+
+        let (first, last) = (outline[0], outline[outline.len() - 1]);
+        if outline.len() > 1
+            && (first[0] - last[0]).abs() < 1e-6
+            && (first[1] - last[1]).abs() < 1e-6
+        {
+            outline.pop();
+        }
+
+        outline
+    }
+
+    pub(crate) fn to_mesh(&self) -> Mesh {
+        let mut mesh = Mesh::default();
+
+        let center = mesh.add_vertex([0.0, 0.0]);
+        let outline: Vec<i32> = self
+            .outline()
+            .into_iter()
+            .map(|point| mesh.add_vertex(point))
+            .collect();
+
+        for i in 0..outline.len() {
+            mesh.add_triangle(center, outline[i], outline[(i + 1) % outline.len()]);
+        }
+
+        mesh
+    }
+
+    pub(crate) fn to_stroke_mesh(&self) -> Mesh<StrokeVertex> {
+        stroke_mesh(&self.outline())
+    }
+}
+
+impl Default for RoundedRect {
+    fn default() -> Self {
+        RoundedRect {
+            data: Default::default(),
+            radii: [5.0; 4],
+            detail: 8,
+            texture: None,
+        }
+    }
+}
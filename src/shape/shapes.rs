@@ -1,23 +1,109 @@
 use wgpu::Color;
 
-use crate::render::instance::Instance;
+use crate::render::instance::{Instance, SdfInstance, SdfKind, StrokeInstance};
+use crate::transform::{from_parts, multiply, translation, Affine, IDENTITY};
 
+#[derive(Clone)]
 pub struct BasicShapeData {
     pub scale: (f32, f32),
     pub pos: (f32, f32),
     pub rotation: f32,
-    pub color: (f32, f32, f32),
+    pub color: (f32, f32, f32, f32),
     pub layer: u16,
+    pub fill: bool,
+    pub stroke_width: f32,
+    pub stroke_color: (f32, f32, f32, f32),
+    pub screen_space: bool,
+    pub anchor: (f32, f32),
+    /// rows of an affine matrix which gets applied after everything else
+    pub transform: [[f32; 3]; 2],
+}
+
+/// scaled shape -> frame
+///
+/// the anchor gets moved to the position and the shape rotated around it, the transform gets applied last
+pub(crate) fn shape_matrix(
+    pos: (f32, f32),
+    rotation: f32,
+    scale: (f32, f32),
+    anchor: (f32, f32),
+    transform: &Affine,
+) -> Affine {
+    let anchor = [-anchor.0 * scale.0 / 2.0, -anchor.1 * scale.1 / 2.0];
+    let placed = multiply(
+        &from_parts([pos.0, pos.1], rotation, [1.0, 1.0]),
+        &translation(anchor),
+    );
+
+    multiply(transform, &placed)
 }
 
 impl From<&BasicShapeData> for Instance {
     fn from(data: &BasicShapeData) -> Self {
         Instance {
-            position: [data.pos.0, data.pos.1],
+            transform: data.matrix(),
             scale: [data.scale.0, data.scale.1],
-            rotation: data.rotation,
-            color: [data.color.0, data.color.1, data.color.2],
+            color: [data.color.0, data.color.1, data.color.2, data.color.3],
             layer: data.layer as u32,
+            screen_space: data.screen_space as u32,
+        }
+    }
+}
+
+impl From<&BasicShapeData> for Option<StrokeInstance> {
+    fn from(data: &BasicShapeData) -> Self {
+        if data.stroke_width <= 0.0 {
+            return None;
+        }
+
+        Some(StrokeInstance {
+            transform: data.matrix(),
+            scale: [data.scale.0, data.scale.1],
+            color: [
+                data.stroke_color.0,
+                data.stroke_color.1,
+                data.stroke_color.2,
+                data.stroke_color.3,
+            ],
+            layer: data.layer as u32,
+            width: data.stroke_width,
+            screen_space: data.screen_space as u32,
+        })
+    }
+}
+
+impl BasicShapeData {
+    /// scaled shape -> frame
+    pub(crate) fn matrix(&self) -> Affine {
+        shape_matrix(
+            self.pos,
+            self.rotation,
+            self.scale,
+            self.anchor,
+            &self.transform,
+        )
+    }
+
+    /// fill and stroke get drawn by the same instance, a shape without fill gets a transparent fill
+    pub(crate) fn to_sdf_instance(&self, kind: SdfKind, corner_radii: [f32; 4]) -> SdfInstance {
+        let max_radius = (self.scale.0.abs()).min(self.scale.1.abs()) / 2.0;
+        let fill_alpha = if self.fill { self.color.3 } else { 0.0 };
+
+        SdfInstance {
+            transform: self.matrix(),
+            scale: [self.scale.0, self.scale.1],
+            color: [self.color.0, self.color.1, self.color.2, fill_alpha],
+            layer: self.layer as u32,
+            kind: kind as u32,
+            corner_radii: corner_radii.map(|radius| radius.clamp(0.0, max_radius)),
+            stroke_width: self.stroke_width.max(0.0),
+            stroke_color: [
+                self.stroke_color.0,
+                self.stroke_color.1,
+                self.stroke_color.2,
+                self.stroke_color.3,
+            ],
+            screen_space: self.screen_space as u32,
         }
     }
 }
@@ -28,32 +114,142 @@ impl Default for BasicShapeData {
             scale: (20.0, 20.0),
             pos: (0.0, 0.0),
             rotation: 0.0,
-            color: (0.0, 0.0, 1.0),
+            color: (0.0, 0.0, 1.0, 1.0),
             layer: 0,
+            fill: true,
+            stroke_width: 0.0,
+            stroke_color: (0.0, 0.0, 0.0, 1.0),
+            screen_space: false,
+            anchor: (0.0, 0.0),
+            transform: IDENTITY,
         }
     }
 }
 
 pub trait BasicShape {
+    /// shared data of the shape
+    fn data(&self) -> &BasicShapeData;
+
+    /// shared data of the shape
+    fn data_mut(&mut self) -> &mut BasicShapeData;
+
     /// size of the shape
-    fn scale(&mut self, width: f32, height: f32) -> &mut Self;
+    fn scale(&mut self, width: f32, height: f32) -> &mut Self {
+        self.data_mut().scale = (width, height);
+        self
+    }
 
-    /// location of the shape in the frame
-    fn pos(&mut self, x: f32, y: f32) -> &mut Self;
+    /// location of the [anchor](BasicShape::anchor) of the shape in the frame
+    fn pos(&mut self, x: f32, y: f32) -> &mut Self {
+        self.data_mut().pos = (x, y);
+        self
+    }
 
-    /// rotation of the shape in radians
-    fn rotation(&mut self, rotation: f32) -> &mut Self;
+    /// rotation of the shape around its [anchor](BasicShape::anchor) in radians
+    fn rotation(&mut self, rotation: f32) -> &mut Self {
+        self.data_mut().rotation = rotation;
+        self
+    }
 
-    /// fill color of the shape
-    fn color(&mut self, red: f32, green: f32, blue: f32) -> &mut Self;
+    /// point of the shape which is at the [pos](BasicShape::pos) and which it rotates around
+    ///
+    /// relative to the size of the shape, (0,0) -> center, (-1,-1) -> bottom left, (1,1) -> top right
+    fn anchor(&mut self, x: f32, y: f32) -> &mut Self {
+        self.data_mut().anchor = (x, y);
+        self
+    }
 
     /// fill color of the shape
-    fn color_from_color(&mut self, color: Color) -> &mut Self;
+    fn color(&mut self, red: f32, green: f32, blue: f32) -> &mut Self {
+        let data = self.data_mut();
+        data.color = (red, green, blue, data.color.3);
+        self
+    }
+
+    /// fill color of the shape including its alpha
+    fn color_from_color(&mut self, color: Color) -> &mut Self {
+        self.data_mut().color = (
+            color.r as f32,
+            color.g as f32,
+            color.b as f32,
+            color.a as f32,
+        );
+        self
+    }
+
+    /// opacity of the shape
+    ///
+    /// 1.0 -> opaque, 0.0 -> invisible
+    fn alpha(&mut self, alpha: f32) -> &mut Self {
+        self.data_mut().color.3 = alpha;
+        self
+    }
 
     /// render layer of the shape
     ///
     /// higher layer -> foreground
-    fn layer(&mut self, layer: u16) -> &mut Self;
+    fn layer(&mut self, layer: u16) -> &mut Self {
+        self.data_mut().layer = layer;
+        self
+    }
+
+    /// if the inside of the shape gets filled
+    ///
+    /// false -> only the stroke gets rendered
+    fn fill(&mut self, fill: bool) -> &mut Self {
+        self.data_mut().fill = fill;
+        self
+    }
+
+    /// width of the stroke along the inside of the outline in frame units
+    ///
+    /// 0.0 -> no stroke
+    fn stroke_width(&mut self, stroke_width: f32) -> &mut Self {
+        self.data_mut().stroke_width = stroke_width;
+        self
+    }
 
-    fn to_instance(&self) -> Instance;
+    /// stroke color of the shape
+    fn stroke_color(&mut self, red: f32, green: f32, blue: f32) -> &mut Self {
+        let data = self.data_mut();
+        data.stroke_color = (red, green, blue, data.stroke_color.3);
+        self
+    }
+
+    /// stroke color of the shape including its alpha
+    fn stroke_color_from_color(&mut self, color: Color) -> &mut Self {
+        self.data_mut().stroke_color = (
+            color.r as f32,
+            color.g as f32,
+            color.b as f32,
+            color.a as f32,
+        );
+        self
+    }
+
+    /// affine matrix which gets applied after the [pos](BasicShape::pos), [rotation](BasicShape::rotation) and [scale](BasicShape::scale)
+    ///
+    /// rows of a 2x3 matrix, x' = m[0][0] * x + m[0][1] * y + m[0][2].
+    /// allows skew and reflection, the stroke gets transformed together with the shape
+    fn transform(&mut self, matrix: [[f32; 3]; 2]) -> &mut Self {
+        self.data_mut().transform = matrix;
+        self
+    }
+
+    /// if the shape is positioned in pixels on the screen instead of in the world
+    ///
+    /// screen space shapes ignore the camera and the frame offset, (0,0) -> center of the screen.
+    /// the layer still decides which shapes are in front
+    fn screen_space(&mut self, screen_space: bool) -> &mut Self {
+        self.data_mut().screen_space = screen_space;
+        self
+    }
+
+    fn to_instance(&self) -> Instance {
+        self.data().into()
+    }
+
+    fn to_stroke_instance(&self) -> Option<StrokeInstance> {
+        self.data().into()
+    }
 }
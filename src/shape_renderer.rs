@@ -1,55 +1,107 @@
-use std::collections::hash_map::Entry;
-use std::collections::{BTreeMap, HashMap};
-use std::f32::consts::PI;
-
-use image::{DynamicImage, GenericImage, GenericImageView, ImageBuffer};
-use rectangle_pack::{
-    contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
-    TargetBin,
-};
-use wgpu::util::DeviceExt;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use bytemuck::Pod;
+use image::RgbaImage;
 use wgpu::{
-    BindGroup, BindGroupLayout, Color, CommandEncoder, Device, Queue, RenderPipeline,
-    SamplerDescriptor, SurfaceConfiguration, TextureView,
+    BindGroup, BindGroupLayout, BlendState, Buffer, Color, CommandEncoder, Device, Queue,
+    RenderPipeline, Sampler, SamplerDescriptor, SurfaceConfiguration, TextureFormat, TextureView,
 };
 use wgpu_noboiler::buffer::{BufferCreator, SimpleBuffer};
-use wgpu_noboiler::render_pass::RenderPassCreator;
-use wgpu_noboiler::render_pipeline::RenderPipelineCreator;
 use wgpu_noboiler::vertex::Vertex;
 
-use crate::render::depth_buffer::DepthBuffer;
-use crate::render::instance::{Instance, TextureInstance};
+use crate::camera::{frame_view_matrix, screen_view_matrix, uniform_columns, Camera2D, ViewMatrix};
+use crate::error::Error;
+use crate::hit_test::{HitShape, HitTester, ShapeId};
+use crate::render::atlas::{AtlasUpdate, Placement, TextureAtlas, TextureId};
+use crate::render::depth_buffer::{DepthBuffer, MultisampleBuffer};
+use crate::render::dynamic_buffer::DynamicBuffer;
+use crate::render::instance::{
+    per_vertex, Instance, SdfInstance, SdfKind, StrokeInstance, TextureInstance,
+};
+use crate::render::mesh::Mesh;
+use crate::render::offscreen::OffscreenTarget;
+use crate::render::render_pipeline::RenderPipelineCreator;
+use crate::render::stroke::{stroke_mesh, StrokeVertex};
 use crate::render::vertex::Vertex as OwnVertex;
+use crate::retained::{GroupHandle, RetainedShape, RetainedShapes, ShapeHandle};
+use crate::shape::arc::{Arc, ArcGeometry, Pie, Ring};
+use crate::shape::group::Group;
 use crate::shape::image::Image;
-use crate::shape::oval::Oval;
-use crate::shape::rect::Rect;
-use crate::shape::shapes::BasicShape;
+use crate::shape::line::Line;
+use crate::shape::oval::{oval_indices, oval_outline, Oval};
+use crate::shape::path::{Path, PathShape, PathStyle};
+use crate::shape::polygon::Polygon;
+use crate::shape::rect::{Rect, RECT_INDICES, RECT_OUTLINE, RECT_VERTICES};
+use crate::shape::rounded_rect::RoundedRect;
+use crate::shape::shapes::{BasicShape, BasicShapeData};
+
+/// how [Rect], [Oval], [RoundedRect] and straight [Line] shapes get drawn
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    /// triangles with hard edges, ovals use their segment count
+    Tessellated,
+    /// one quad per shape with a signed distance shader which smooths the edges at every zoom,
+    /// all of these shapes get blended sorted by their layer
+    Sdf,
+}
 
 /// helps to draw basic [BasicShapes](BasicShape)
 pub struct ShapeRenderer {
     shape_render_pipeline: RenderPipeline,
     texture_render_pipeline: RenderPipeline,
+    translucent_shape_render_pipeline: RenderPipeline,
+    translucent_texture_render_pipeline: RenderPipeline,
+    stroke_render_pipeline: RenderPipeline,
+    translucent_stroke_render_pipeline: RenderPipeline,
+    sdf_render_pipeline: RenderPipeline,
+    mesh_pipelines: MeshPipelines,
+    translucent_mesh_pipelines: MeshPipelines,
 
     recs: Vec<Rect>,
     ovals: Vec<Oval>,
     images: Vec<Image>,
-
-    frame_group_layout: BindGroupLayout,
+    lines: Vec<Line>,
+    polygons: Vec<Polygon>,
+    paths: Vec<PathShape>,
+    rounded_rects: Vec<RoundedRect>,
+    arcs: Vec<Arc>,
+    pies: Vec<Pie>,
+    rings: Vec<Ring>,
+    retained: RetainedShapes,
+
+    view_buffer: Buffer,
+    pixel_size_buffer: Buffer,
+    screen_buffer: Buffer,
+    frame_bind_group: BindGroup,
     frame_size: (f32, f32),
     frame_offset: (f32, f32),
+    camera: Option<Camera2D>,
+    surface_size: (u32, u32),
+    format: TextureFormat,
+    path_tolerance: f32,
+    image_hit_alpha: Option<f32>,
+    render_mode: RenderMode,
 
     background_color: Color,
 
     depth_texture: DepthBuffer,
+    sample_count: u32,
+    multisample_texture: Option<MultisampleBuffer>,
 
     texture_group_layout: BindGroupLayout,
-    texture: Option<TextureView>,
-    texture_size: u32,
-    textures: Vec<DynamicImage>,
-    textures_cords: Vec<((f32, f32), (f32, f32))>,
+    sampler: Sampler,
+    texture_bind_group: Option<BindGroup>,
+    /// pages of the atlas as layers
+    atlas_texture: Option<wgpu::Texture>,
+    atlas: TextureAtlas,
 
     rect_vertex_buffer: SimpleBuffer,
     rect_indices_buffer: SimpleBuffer,
+    rect_stroke_vertex_buffer: SimpleBuffer,
+    rect_stroke_indices_buffer: SimpleBuffer,
+
+    buffers: FrameBuffers,
 }
 
 impl ShapeRenderer {
@@ -58,7 +110,44 @@ impl ShapeRenderer {
     ///
     /// can be reused with [ShapeRenderer::clear] function
     pub fn new(device: &Device, config: &SurfaceConfiguration) -> ShapeRenderer {
-        let frame_size_group_layout =
+        Self::with_sample_count(device, config, 1)
+    }
+
+    /// creates a new [ShapeRenderer] which renders with multisample anti-aliasing
+    ///
+    /// sample_count has to be 1, 2, 4 or 8. 1 -> no multisampling,
+    /// every count besides 1 and 4 needs [TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES](wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+    /// and has to be supported by the adapter for the surface format
+    pub fn with_sample_count(
+        device: &Device,
+        config: &SurfaceConfiguration,
+        sample_count: u32,
+    ) -> ShapeRenderer {
+        Self::with_format(
+            device,
+            config.format,
+            (config.width, config.height),
+            sample_count,
+        )
+    }
+
+    /// creates a new [ShapeRenderer] which is not bound to a surface
+    ///
+    /// renders into textures of the given format and size,
+    /// used for an [OffscreenTarget] with [ShapeRenderer::render_to_image]
+    pub fn with_format(
+        device: &Device,
+        format: TextureFormat,
+        size: (u32, u32),
+        sample_count: u32,
+    ) -> ShapeRenderer {
+        assert!(
+            matches!(sample_count, 1 | 2 | 4 | 8),
+            "sample count has to be 1, 2, 4 or 8 but was {}",
+            sample_count
+        );
+
+        let frame_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 entries: &[
                     wgpu::BindGroupLayoutEntry {
@@ -81,6 +170,16 @@ impl ShapeRenderer {
                         },
                         count: None,
                     },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::VERTEX,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
                 ],
                 label: Some("Frame Bind group"),
             });
@@ -93,7 +192,7 @@ impl ShapeRenderer {
                         visibility: wgpu::ShaderStages::FRAGMENT,
                         ty: wgpu::BindingType::Texture {
                             multisampled: false,
-                            view_dimension: wgpu::TextureViewDimension::D2,
+                            view_dimension: wgpu::TextureViewDimension::D2Array,
                             sample_type: wgpu::TextureSampleType::Float { filterable: true },
                         },
                         count: None,
@@ -113,9 +212,10 @@ impl ShapeRenderer {
         let shape_render_pipeline = RenderPipelineCreator::from_shader_code(
             include_str!("../resources/shape_shader.wgsl"),
             device,
-            config,
+            format,
         )
-        .add_bind_group(&frame_size_group_layout)
+        .sample_count(sample_count)
+        .add_bind_group(&frame_group_layout)
         .add_vertex_buffer(OwnVertex::descriptor())
         .add_vertex_buffer(Instance::descriptor())
         .depth_stencil(wgpu::DepthStencilState {
@@ -130,12 +230,58 @@ impl ShapeRenderer {
         let texture_render_pipeline = RenderPipelineCreator::from_shader_code(
             include_str!("../resources/texture_shader.wgsl"),
             device,
-            config,
+            format,
+        )
+        .sample_count(sample_count)
+        .add_bind_group(&frame_group_layout)
+        .add_bind_group(&texture_bind_group_layout)
+        .add_vertex_buffer(OwnVertex::descriptor())
+        .add_vertex_buffer(TextureInstance::descriptor())
+        .depth_stencil(wgpu::DepthStencilState {
+            format: DepthBuffer::DEPTH_FORMAT,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        })
+        .build();
+
+        let translucent_shape_render_pipeline = RenderPipelineCreator::from_shader_code(
+            include_str!("../resources/shape_shader.wgsl"),
+            device,
+            format,
+        )
+        .sample_count(sample_count)
+        .add_bind_group(&frame_group_layout)
+        .add_vertex_buffer(OwnVertex::descriptor())
+        .add_vertex_buffer(Instance::descriptor())
+        .depth_stencil(Self::translucent_depth_stencil())
+        .blend_state(BlendState::ALPHA_BLENDING)
+        .build();
+
+        let translucent_texture_render_pipeline = RenderPipelineCreator::from_shader_code(
+            include_str!("../resources/texture_shader.wgsl"),
+            device,
+            format,
         )
-        .add_bind_group(&frame_size_group_layout)
+        .sample_count(sample_count)
+        .add_bind_group(&frame_group_layout)
         .add_bind_group(&texture_bind_group_layout)
         .add_vertex_buffer(OwnVertex::descriptor())
         .add_vertex_buffer(TextureInstance::descriptor())
+        .depth_stencil(Self::translucent_depth_stencil())
+        .blend_state(BlendState::ALPHA_BLENDING)
+        .build();
+
+        let stroke_render_pipeline = RenderPipelineCreator::from_shader_code(
+            include_str!("../resources/stroke_shader.wgsl"),
+            device,
+            format,
+        )
+        .sample_count(sample_count)
+        .add_bind_group(&frame_group_layout)
+        .add_vertex_buffer(StrokeVertex::descriptor())
+        .add_vertex_buffer(StrokeInstance::descriptor())
         .depth_stencil(wgpu::DepthStencilState {
             format: DepthBuffer::DEPTH_FORMAT,
             depth_write_enabled: true,
@@ -145,475 +291,2152 @@ impl ShapeRenderer {
         })
         .build();
 
+        let translucent_stroke_render_pipeline = RenderPipelineCreator::from_shader_code(
+            include_str!("../resources/stroke_shader.wgsl"),
+            device,
+            format,
+        )
+        .sample_count(sample_count)
+        .add_bind_group(&frame_group_layout)
+        .add_vertex_buffer(StrokeVertex::descriptor())
+        .add_vertex_buffer(StrokeInstance::descriptor())
+        .depth_stencil(Self::translucent_depth_stencil())
+        .blend_state(BlendState::ALPHA_BLENDING)
+        .build();
+
+        let sdf_render_pipeline = RenderPipelineCreator::from_shader_code(
+            include_str!("../resources/sdf_shader.wgsl"),
+            device,
+            format,
+        )
+        .sample_count(sample_count)
+        .add_bind_group(&frame_group_layout)
+        .add_vertex_buffer(OwnVertex::descriptor())
+        .add_vertex_buffer(SdfInstance::descriptor())
+        .depth_stencil(Self::translucent_depth_stencil())
+        .blend_state(BlendState::ALPHA_BLENDING)
+        .build();
+
+        let mesh_pipelines = MeshPipelines::new(
+            device,
+            format,
+            sample_count,
+            &frame_group_layout,
+            &texture_bind_group_layout,
+            false,
+        );
+        let translucent_mesh_pipelines = MeshPipelines::new(
+            device,
+            format,
+            sample_count,
+            &frame_group_layout,
+            &texture_bind_group_layout,
+            true,
+        );
+
         let rect_vertex_buffer = BufferCreator::vertex(device)
             .label("Rect VertexBuffer")
-            .data(vec![
-                OwnVertex {
-                    position: [1.0, 1.0],
-                },
-                OwnVertex {
-                    position: [-1.0, 1.0],
-                },
-                OwnVertex {
-                    position: [-1.0, -1.0],
-                },
-                OwnVertex {
-                    position: [1.0, -1.0],
-                },
-            ])
+            .data(
+                RECT_VERTICES
+                    .map(|position| OwnVertex { position })
+                    .to_vec(),
+            )
             .build();
 
         let rect_indices_buffer = BufferCreator::indices(device)
             .label("Rect IndicesBuffer")
-            .data(vec![0, 1, 2, 0, 2, 3])
+            .data(RECT_INDICES.to_vec())
+            .build();
+
+        let rect_stroke_mesh = stroke_mesh(&RECT_OUTLINE);
+
+        let rect_stroke_vertex_buffer = BufferCreator::vertex(device)
+            .label("Rect Stroke VertexBuffer")
+            .data(rect_stroke_mesh.vertices)
+            .build();
+
+        let rect_stroke_indices_buffer = BufferCreator::indices(device)
+            .label("Rect Stroke IndicesBuffer")
+            .data(rect_stroke_mesh.indices)
             .build();
 
+        // mat3x3 -> three columns padded to vec4
+        let view_buffer = Self::create_uniform_buffer(device, "View Buffer", 48);
+        let pixel_size_buffer = Self::create_uniform_buffer(device, "Pixel size Buffer", 4);
+        let screen_buffer = Self::create_uniform_buffer(device, "Screen Buffer", 48);
+
+        let frame_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &frame_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: view_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: pixel_size_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: screen_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("frame_size_bind_group"),
+        });
+
         ShapeRenderer {
             shape_render_pipeline,
             texture_render_pipeline,
+            translucent_shape_render_pipeline,
+            translucent_texture_render_pipeline,
+            stroke_render_pipeline,
+            translucent_stroke_render_pipeline,
+            sdf_render_pipeline,
+            mesh_pipelines,
+            translucent_mesh_pipelines,
 
             recs: vec![],
             ovals: vec![],
             images: vec![],
-
-            frame_group_layout: frame_size_group_layout,
+            lines: vec![],
+            polygons: vec![],
+            paths: vec![],
+            rounded_rects: vec![],
+            arcs: vec![],
+            pies: vec![],
+            rings: vec![],
+            retained: RetainedShapes::new(),
+
+            view_buffer,
+            pixel_size_buffer,
+            screen_buffer,
+            frame_bind_group,
             frame_size: (800.0, 600.0),
             frame_offset: (0.0, 0.0),
+            camera: None,
+            surface_size: size,
+            format,
+            path_tolerance: 0.25,
+            image_hit_alpha: None,
+            render_mode: RenderMode::Tessellated,
 
             background_color: Color::WHITE,
 
-            depth_texture: DepthBuffer::create_depth_texture(device, config, "depth_texture"),
+            depth_texture: DepthBuffer::create_depth_texture(
+                device,
+                size,
+                sample_count,
+                "depth_texture",
+            ),
+            sample_count,
+            multisample_texture: Self::create_multisample_texture(
+                device,
+                size,
+                format,
+                sample_count,
+            ),
 
             texture_group_layout: texture_bind_group_layout,
-            texture: None,
-            texture_size: 512,
-            textures: vec![],
-            textures_cords: vec![],
+            sampler: device.create_sampler(&SamplerDescriptor::default()),
+            texture_bind_group: None,
+            atlas_texture: None,
+            atlas: TextureAtlas::new(&device.limits()),
             rect_vertex_buffer,
             rect_indices_buffer,
+            rect_stroke_vertex_buffer,
+            rect_stroke_indices_buffer,
+
+            buffers: FrameBuffers::new(device),
         }
     }
 
-    fn frame_bind_group(&self, device: &Device) -> BindGroup {
-        let frame_size_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Frame size Buffer"),
-            contents: bytemuck::cast_slice(&[self.frame_size.0, self.frame_size.1]),
+    fn create_uniform_buffer(device: &Device, label: &str, size: u64) -> Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
+            mapped_at_creation: false,
+        })
+    }
 
-        let frame_offset_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Frame offset Buffer"),
-            contents: bytemuck::cast_slice(&[self.frame_offset.0, self.frame_offset.1]),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
+    /// the color target of the render passes, only needed when multisampling
+    fn create_multisample_texture(
+        device: &Device,
+        size: (u32, u32),
+        format: TextureFormat,
+        sample_count: u32,
+    ) -> Option<MultisampleBuffer> {
+        if sample_count <= 1 {
+            return None;
+        }
 
-        device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &self.frame_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: frame_size_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: frame_offset_buffer.as_entire_binding(),
-                },
-            ],
-            label: Some("frame_size_bind_group"),
-        })
+        Some(MultisampleBuffer::create_multisample_texture(
+            device,
+            size,
+            format,
+            sample_count,
+            "multisample_texture",
+        ))
     }
 
-    fn texture_bind_group(&self, device: &Device) -> Option<BindGroup> {
-        self.texture.as_ref()?;
+    /// renders into the multisampled texture and resolves it into the view if multisampling is used
+    fn color_attachment<'a>(
+        &'a self,
+        texture_view: &'a TextureView,
+        load: wgpu::LoadOp<Color>,
+    ) -> wgpu::RenderPassColorAttachment<'a> {
+        let (view, resolve_target) = match &self.multisample_texture {
+            Some(multisample_texture) => (&multisample_texture.view, Some(texture_view)),
+            None => (texture_view, None),
+        };
 
-        Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &self.texture_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(self.texture.as_ref().unwrap()),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(
-                        &device.create_sampler(&SamplerDescriptor::default()),
-                    ),
-                },
-            ],
-            label: Some("diffuse_bind_group"),
-        }))
+        wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target,
+            ops: wgpu::Operations { load, store: true },
+        }
+    }
+
+    /// translucent shapes get tested against the opaque ones but do not hide each other
+    fn translucent_depth_stencil() -> wgpu::DepthStencilState {
+        wgpu::DepthStencilState {
+            format: DepthBuffer::DEPTH_FORMAT,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::LessEqual,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }
     }
 
     /// renders the current [BasicShapes](BasicShape) which can be added with [ShapeRenderer::rect], [ShapeRenderer::oval], ...
-    pub fn render<'a, 'b: 'a>(
-        &'b self,
+    ///
+    /// opaque shapes get rendered first, translucent shapes get blended on top of them sorted by their layer
+    ///
+    /// the shapes get written into buffers which are reused every frame through the queue,
+    /// the encoder has to be submitted before the next call
+    pub fn render(
+        &mut self,
         encoder: &mut CommandEncoder,
         texture_view: &TextureView,
         device: &Device,
+        queue: &Queue,
     ) {
-        let rect_instance_buffer = self.generate_rect_buffer(device);
-        let oval_buffers = self.generate_oval_buffer(device);
-        let image_instance_buffer = self.generate_image_buffer(device);
+        let frame = self.update_buffers(device, queue);
 
-        let frame_bind_group = self.frame_bind_group(device);
-        let texture_bind_group = self.texture_bind_group(device);
+        let buffers = &self.buffers;
+        let retained = &self.retained;
+        let frame_bind_group = &self.frame_bind_group;
+        let texture_bind_group = &self.texture_bind_group;
 
-        let mut render_pass = RenderPassCreator::new(texture_view)
-            .depth_stencil_attachment(wgpu::RenderPassDepthStencilAttachment {
-                view: &self.depth_texture.view,
-                depth_ops: Some(wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(1.0),
-                    store: true,
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Render Pass"),
+                color_attachments: &[Some(
+                    self.color_attachment(texture_view, wgpu::LoadOp::Clear(self.background_color)),
+                )],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_texture.view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: true,
+                    }),
+                    stencil_ops: None,
                 }),
-                stencil_ops: None,
-            })
-            .clear_color(self.background_color)
-            .build(encoder);
-
-        render_pass.set_pipeline(&self.shape_render_pipeline);
-        render_pass.set_bind_group(0, &frame_bind_group, &[]);
-
-        //rects
-        render_pass.set_vertex_buffer(0, self.rect_vertex_buffer.slice());
-        render_pass.set_index_buffer(self.rect_indices_buffer.slice(), wgpu::IndexFormat::Uint32);
-
-        render_pass.set_vertex_buffer(1, rect_instance_buffer.slice());
-
-        render_pass.draw_indexed(
-            0..self.rect_indices_buffer.size(),
-            0,
-            0..rect_instance_buffer.size(),
-        );
+            });
 
-        //ovals
+            render_pass.set_pipeline(&self.shape_render_pipeline);
+            render_pass.set_bind_group(0, frame_bind_group, &[]);
 
-        for InstanceBufferGroup(oval_vertex_buffer, oval_indices_buffer, oval_instance_buffer) in
-            &oval_buffers
-        {
-            render_pass.set_vertex_buffer(0, oval_vertex_buffer.slice());
-            render_pass.set_index_buffer(oval_indices_buffer.slice(), wgpu::IndexFormat::Uint32);
+            //rects
+            render_pass.set_vertex_buffer(0, self.rect_vertex_buffer.slice());
+            render_pass
+                .set_index_buffer(self.rect_indices_buffer.slice(), wgpu::IndexFormat::Uint32);
 
-            render_pass.set_vertex_buffer(1, oval_instance_buffer.slice());
+            render_pass.set_vertex_buffer(1, buffers.rect_instances.slice());
 
             render_pass.draw_indexed(
-                0..oval_indices_buffer.size(),
+                0..self.rect_indices_buffer.size(),
                 0,
-                0..oval_instance_buffer.size(),
+                0..buffers.rect_instances.size(),
             );
-        }
-
-        //texture
-
-        if texture_bind_group.is_none() {
-            return;
-        }
 
-        render_pass.set_pipeline(&self.texture_render_pipeline);
-        render_pass.set_bind_group(0, &frame_bind_group, &[]);
-        render_pass.set_bind_group(1, texture_bind_group.as_ref().unwrap(), &[]);
+            if let Some(instance_buffer) = retained.rects.buffer() {
+                render_pass.set_vertex_buffer(1, instance_buffer.slice());
+                render_pass.draw_indexed(
+                    0..self.rect_indices_buffer.size(),
+                    0,
+                    0..instance_buffer.size(),
+                );
+            }
 
-        render_pass.set_vertex_buffer(0, self.rect_vertex_buffer.slice());
-        render_pass.set_index_buffer(self.rect_indices_buffer.slice(), wgpu::IndexFormat::Uint32);
+            //ovals
 
-        render_pass.set_vertex_buffer(1, image_instance_buffer.slice());
+            for (detail, OvalBuffers { fill, .. }) in &buffers.ovals {
+                let InstanceBufferGroup(vertex_buffer, indices_buffer, instance_buffer) = fill;
 
-        render_pass.draw_indexed(
-            0..self.rect_indices_buffer.size(),
-            0,
-            0..image_instance_buffer.size(),
-        );
-    }
+                render_pass.set_vertex_buffer(0, vertex_buffer.slice());
+                render_pass.set_index_buffer(indices_buffer.slice(), wgpu::IndexFormat::Uint32);
 
-    /// clears the current drawn [BasicShapes](BasicShape) which can be added with [ShapeRenderer::rect], [ShapeRenderer::oval], ...
-    pub fn clear(&mut self) {
-        self.recs.clear();
-        self.ovals.clear();
-        self.images.clear();
-    }
+                render_pass.set_vertex_buffer(1, instance_buffer.slice());
 
-    /// sets the current [frame_size](ShapeRenderer::frame_size)
-    pub fn set_frame_size(&mut self, frame_size: (f32, f32)) -> &mut Self {
-        self.frame_size = frame_size;
-        self
-    }
+                render_pass.draw_indexed(0..indices_buffer.size(), 0, 0..instance_buffer.size());
 
-    /// frame_size.0 is the with in which [BasicShape] get displayed.
-    ///
-    /// frame_size.1 is the height in which [BasicShape] get displayed
-    ///
-    /// (0,0) -> center of screen
-    pub fn frame_size(&self) -> (f32, f32) {
-        self.frame_size
-    }
+                if let Some(instance_buffer) = retained
+                    .ovals
+                    .get(detail)
+                    .and_then(|ovals| ovals.fill.buffer())
+                {
+                    render_pass.set_vertex_buffer(1, instance_buffer.slice());
+                    render_pass.draw_indexed(
+                        0..indices_buffer.size(),
+                        0,
+                        0..instance_buffer.size(),
+                    );
+                }
+            }
 
-    /// sets the current [frame_offset](ShapeRenderer::frame_offset)
-    pub fn set_frame_offset(&mut self, frame_offset: (f32, f32)) -> &mut Self {
-        self.frame_offset = frame_offset;
-        self
-    }
+            //meshes
 
-    /// frame_offset are values which get added to all [BasicShape]
-    pub fn frame_offset(&self) -> (f32, f32) {
-        self.frame_offset
-    }
+            if !frame.meshes.opaque.is_empty() {
+                render_pass.set_pipeline(&self.mesh_pipelines.fill);
+                render_pass.set_bind_group(0, frame_bind_group, &[]);
 
-    /// resizes the depthBuffer should be called on every window resize
-    pub fn resize(&mut self, device: &Device, config: &SurfaceConfiguration) -> &mut Self {
-        self.depth_texture = DepthBuffer::create_depth_texture(device, config, "depth_texture");
-        self
-    }
+                render_pass.set_vertex_buffer(0, buffers.meshes.vertex_buffer.slice());
+                render_pass.set_index_buffer(
+                    buffers.meshes.indices_buffer.slice(),
+                    wgpu::IndexFormat::Uint32,
+                );
 
-    /// sets the clearColor/ backgroundColor
-    pub fn background_color(&mut self, background_color: Color) -> &mut Self {
-        self.background_color = background_color;
-        self
-    }
+                render_pass.set_vertex_buffer(1, buffers.meshes.instance_buffer.slice());
 
-    /// renders [Rect] and returns a Ref to it
-    pub fn rect(&mut self) -> &mut Rect {
-        self.recs.push(Rect::default());
-        self.recs.last_mut().unwrap()
-    }
+                render_pass.draw_indexed(frame.meshes.opaque.clone(), 0, 0..1);
+            }
 
-    fn generate_rect_buffer(&self, device: &Device) -> SimpleBuffer {
-        let instances: Vec<_> = self.recs.iter().map(|rect| rect.to_instance()).collect();
+            //strokes
 
-        let instances_buffer = BufferCreator::vertex(device)
-            .label("Rect InstanceBuffer")
-            .data(instances)
-            .build();
+            render_pass.set_pipeline(&self.stroke_render_pipeline);
+            render_pass.set_bind_group(0, frame_bind_group, &[]);
 
-        instances_buffer
-    }
+            render_pass.set_vertex_buffer(0, self.rect_stroke_vertex_buffer.slice());
+            render_pass.set_index_buffer(
+                self.rect_stroke_indices_buffer.slice(),
+                wgpu::IndexFormat::Uint32,
+            );
 
-    /// renders [Oval] and returns a Ref to it
-    pub fn oval(&mut self) -> &mut Oval {
-        self.ovals.push(Oval::default());
-        self.ovals.last_mut().unwrap()
-    }
+            render_pass.set_vertex_buffer(1, buffers.rect_stroke_instances.slice());
 
-    fn generate_oval_buffer(&self, device: &Device) -> Vec<InstanceBufferGroup> {
-        let mut ovals = HashMap::<u32, Vec<&Oval>>::new();
+            render_pass.draw_indexed(
+                0..self.rect_stroke_indices_buffer.size(),
+                0,
+                0..buffers.rect_stroke_instances.size(),
+            );
 
-        for oval in &self.ovals {
-            if let Entry::Vacant(e) = ovals.entry(oval.detail) {
-                e.insert(vec![oval]);
-            } else {
-                ovals.get_mut(&oval.detail).unwrap().push(oval);
+            if let Some(instance_buffer) = retained.rect_strokes.buffer() {
+                render_pass.set_vertex_buffer(1, instance_buffer.slice());
+                render_pass.draw_indexed(
+                    0..self.rect_stroke_indices_buffer.size(),
+                    0,
+                    0..instance_buffer.size(),
+                );
             }
-        }
 
-        let mut instance_buffer_groups = vec![];
+            for (detail, OvalBuffers { stroke, .. }) in &buffers.ovals {
+                let InstanceBufferGroup(vertex_buffer, indices_buffer, instance_buffer) = stroke;
 
-        for (detail, ovals) in ovals {
-            let vertices: Vec<_> = (0..detail)
-                .map(|i| {
-                    let angle = PI * 2.0 / detail as f32 * i as f32;
+                render_pass.set_vertex_buffer(0, vertex_buffer.slice());
+                render_pass.set_index_buffer(indices_buffer.slice(), wgpu::IndexFormat::Uint32);
 
-                    OwnVertex {
-                        position: [angle.cos(), angle.sin()],
-                    }
-                })
-                .collect();
+                render_pass.set_vertex_buffer(1, instance_buffer.slice());
 
-            let vertex_buffer = BufferCreator::vertex(device)
-                .label("Rect VertexBuffer")
-                .data(vertices)
-                .build();
+                render_pass.draw_indexed(0..indices_buffer.size(), 0, 0..instance_buffer.size());
 
-            let indices: Vec<_> = (0..(detail as i32 - 2))
-                .flat_map(|i| [0, i + 1, i + 2])
-                .collect();
+                if let Some(instance_buffer) = retained
+                    .ovals
+                    .get(detail)
+                    .and_then(|ovals| ovals.stroke.buffer())
+                {
+                    render_pass.set_vertex_buffer(1, instance_buffer.slice());
+                    render_pass.draw_indexed(
+                        0..indices_buffer.size(),
+                        0,
+                        0..instance_buffer.size(),
+                    );
+                }
+            }
 
-            let indices_buffer = BufferCreator::indices(device)
-                .label("Rect IndicesBuffer")
-                .data(indices)
-                .build();
+            if !frame.stroke_meshes.opaque.is_empty() {
+                render_pass.set_pipeline(&self.mesh_pipelines.stroke);
+                render_pass.set_bind_group(0, frame_bind_group, &[]);
 
-            let instances: Vec<_> = ovals.iter().map(|oval| oval.to_instance()).collect();
+                render_pass.set_vertex_buffer(0, buffers.stroke_meshes.vertex_buffer.slice());
+                render_pass.set_index_buffer(
+                    buffers.stroke_meshes.indices_buffer.slice(),
+                    wgpu::IndexFormat::Uint32,
+                );
 
-            let instances_buffer = BufferCreator::vertex(device)
-                .label("Rect InstanceBuffer")
-                .data(instances)
-                .build();
+                render_pass.set_vertex_buffer(1, buffers.stroke_meshes.instance_buffer.slice());
 
-            instance_buffer_groups.push(InstanceBufferGroup(
-                vertex_buffer,
-                indices_buffer,
-                instances_buffer,
-            ));
+                render_pass.draw_indexed(frame.stroke_meshes.opaque.clone(), 0, 0..1);
+            }
+
+            //texture
+
+            if let Some(texture_bind_group) = texture_bind_group {
+                render_pass.set_pipeline(&self.texture_render_pipeline);
+                render_pass.set_bind_group(0, frame_bind_group, &[]);
+                render_pass.set_bind_group(1, texture_bind_group, &[]);
+
+                render_pass.set_vertex_buffer(0, self.rect_vertex_buffer.slice());
+                render_pass
+                    .set_index_buffer(self.rect_indices_buffer.slice(), wgpu::IndexFormat::Uint32);
+
+                render_pass.set_vertex_buffer(1, buffers.image_instances.slice());
+
+                render_pass.draw_indexed(
+                    0..self.rect_indices_buffer.size(),
+                    0,
+                    0..buffers.image_instances.size(),
+                );
+
+                if let Some(instance_buffer) = retained.images.buffer() {
+                    render_pass.set_vertex_buffer(1, instance_buffer.slice());
+                    render_pass.draw_indexed(
+                        0..self.rect_indices_buffer.size(),
+                        0,
+                        0..instance_buffer.size(),
+                    );
+                }
+
+                if !frame.texture_meshes.opaque.is_empty() {
+                    render_pass.set_pipeline(&self.mesh_pipelines.texture);
+                    render_pass.set_bind_group(0, frame_bind_group, &[]);
+                    render_pass.set_bind_group(1, texture_bind_group, &[]);
+
+                    render_pass.set_vertex_buffer(0, buffers.texture_meshes.vertex_buffer.slice());
+                    render_pass.set_index_buffer(
+                        buffers.texture_meshes.indices_buffer.slice(),
+                        wgpu::IndexFormat::Uint32,
+                    );
+
+                    render_pass
+                        .set_vertex_buffer(1, buffers.texture_meshes.instance_buffer.slice());
+
+                    render_pass.draw_indexed(frame.texture_meshes.opaque.clone(), 0, 0..1);
+                }
+            }
         }
 
-        instance_buffer_groups
-    }
+        //translucent
 
-    /// renders [Image] and returns a Ref to it
-    pub fn image(&mut self, texture_index: usize) -> &mut Image {
-        let mut image = Image::default();
-        match self.textures_cords.get(texture_index) {
-            None => {
-                println!("No texture with the id: {} could be found", texture_index);
-            }
-            Some(cords) => {
-                image.texture_pos = cords.0;
-                image.texture_scale = cords.1;
-            }
-        };
+        if frame.translucent_batches.is_empty() {
+            return;
+        }
 
-        self.images.push(image);
-        self.images.last_mut().unwrap()
-    }
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Translucent Render Pass"),
+            color_attachments: &[Some(
+                self.color_attachment(texture_view, wgpu::LoadOp::Load),
+            )],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_texture.view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                }),
+                stencil_ops: None,
+            }),
+        });
 
-    fn generate_image_buffer(&self, device: &Device) -> SimpleBuffer {
-        let instances: Vec<_> = self
-            .images
-            .iter()
-            .map(|texture| texture.to_instance())
-            .collect();
+        for batch in &frame.translucent_batches {
+            let (pipeline, vertex_buffer, indices_buffer, instance_buffer, indices, instances) =
+                match batch.kind {
+                    ShapeKind::Rect => (
+                        &self.translucent_shape_render_pipeline,
+                        self.rect_vertex_buffer.slice(),
+                        self.rect_indices_buffer.slice(),
+                        &buffers.translucent.shape_instances,
+                        0..self.rect_indices_buffer.size(),
+                        batch.instances.clone(),
+                    ),
+                    ShapeKind::Oval(detail) => {
+                        let InstanceBufferGroup(vertex_buffer, indices_buffer, _) =
+                            &buffers.ovals[&detail].fill;
+                        (
+                            &self.translucent_shape_render_pipeline,
+                            vertex_buffer.slice(),
+                            indices_buffer.slice(),
+                            &buffers.translucent.shape_instances,
+                            0..indices_buffer.size(),
+                            batch.instances.clone(),
+                        )
+                    }
+                    ShapeKind::RectStroke => (
+                        &self.translucent_stroke_render_pipeline,
+                        self.rect_stroke_vertex_buffer.slice(),
+                        self.rect_stroke_indices_buffer.slice(),
+                        &buffers.translucent.stroke_instances,
+                        0..self.rect_stroke_indices_buffer.size(),
+                        batch.instances.clone(),
+                    ),
+                    ShapeKind::OvalStroke(detail) => {
+                        let InstanceBufferGroup(vertex_buffer, indices_buffer, _) =
+                            &buffers.ovals[&detail].stroke;
+                        (
+                            &self.translucent_stroke_render_pipeline,
+                            vertex_buffer.slice(),
+                            indices_buffer.slice(),
+                            &buffers.translucent.stroke_instances,
+                            0..indices_buffer.size(),
+                            batch.instances.clone(),
+                        )
+                    }
+                    ShapeKind::Image => {
+                        let Some(texture_bind_group) = texture_bind_group else {
+                            continue;
+                        };
+
+                        render_pass.set_bind_group(1, texture_bind_group, &[]);
+                        (
+                            &self.translucent_texture_render_pipeline,
+                            self.rect_vertex_buffer.slice(),
+                            self.rect_indices_buffer.slice(),
+                            &buffers.translucent.image_instances,
+                            0..self.rect_indices_buffer.size(),
+                            batch.instances.clone(),
+                        )
+                    }
+                    ShapeKind::Mesh => (
+                        &self.translucent_mesh_pipelines.fill,
+                        buffers.meshes.vertex_buffer.slice(),
+                        buffers.meshes.indices_buffer.slice(),
+                        &buffers.meshes.instance_buffer,
+                        frame.meshes.translucent(&batch.instances),
+                        0..1,
+                    ),
+                    ShapeKind::TextureMesh => {
+                        let Some(texture_bind_group) = texture_bind_group else {
+                            continue;
+                        };
+
+                        render_pass.set_bind_group(1, texture_bind_group, &[]);
+                        (
+                            &self.translucent_mesh_pipelines.texture,
+                            buffers.texture_meshes.vertex_buffer.slice(),
+                            buffers.texture_meshes.indices_buffer.slice(),
+                            &buffers.texture_meshes.instance_buffer,
+                            frame.texture_meshes.translucent(&batch.instances),
+                            0..1,
+                        )
+                    }
+                    ShapeKind::Sdf => (
+                        &self.sdf_render_pipeline,
+                        self.rect_vertex_buffer.slice(),
+                        self.rect_indices_buffer.slice(),
+                        &buffers.translucent.sdf_instances,
+                        0..self.rect_indices_buffer.size(),
+                        batch.instances.clone(),
+                    ),
+                    ShapeKind::StrokeMesh => (
+                        &self.translucent_mesh_pipelines.stroke,
+                        buffers.stroke_meshes.vertex_buffer.slice(),
+                        buffers.stroke_meshes.indices_buffer.slice(),
+                        &buffers.stroke_meshes.instance_buffer,
+                        frame.stroke_meshes.translucent(&batch.instances),
+                        0..1,
+                    ),
+                };
 
-        let instances_buffer = BufferCreator::vertex(device)
-            .label("Rect InstanceBuffer")
-            .data(instances)
-            .build();
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, frame_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, vertex_buffer);
+            render_pass.set_index_buffer(indices_buffer, wgpu::IndexFormat::Uint32);
+            render_pass.set_vertex_buffer(1, instance_buffer.slice());
 
-        instances_buffer
+            render_pass.draw_indexed(indices, 0, instances);
+        }
     }
 
-    pub fn add_texture_from_bytes(
+    /// renders the current [BasicShapes](BasicShape) into the [OffscreenTarget] and reads the result back
+    ///
+    /// does not need a window, works with a fallback adapter too.
+    /// the depth and multisample textures get resized to the target if needed
+    pub fn render_to_image(
         &mut self,
-        bytes: &[u8],
         device: &Device,
         queue: &Queue,
-    ) -> &mut Self {
-        self.textures.push(image::load_from_memory(bytes).unwrap());
+        target: &OffscreenTarget,
+    ) -> RgbaImage {
+        assert_eq!(
+            self.format,
+            target.format(),
+            "the target has to use the format of the renderer"
+        );
 
-        self.upload_textures(device, queue);
+        if self.surface_size != target.size() {
+            self.resize_targets(device, target.size());
+        }
 
-        self
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Offscreen Render Encoder"),
+        });
+
+        self.render(&mut encoder, target.view(), device, queue);
+
+        queue.submit(Some(encoder.finish()));
+
+        target.read_image(device, queue)
     }
 
-    /// adds a texture to the usable texturePool
-    pub fn add_textures_from_bytes(
-        &mut self,
-        bytes: &Vec<&[u8]>,
+    /// writes everything which gets drawn this frame into the persistent buffers
+    fn update_buffers(&mut self, device: &Device, queue: &Queue) -> FrameData {
+        queue.write_buffer(
+            &self.view_buffer,
+            0,
+            bytemuck::cast_slice(&uniform_columns(&self.view_matrix())),
+        );
+        queue.write_buffer(
+            &self.pixel_size_buffer,
+            0,
+            bytemuck::cast_slice(&[self.pixel_size()]),
+        );
+        queue.write_buffer(
+            &self.screen_buffer,
+            0,
+            bytemuck::cast_slice(&uniform_columns(&screen_view_matrix(self.surface_size))),
+        );
+
+        let rect_instances = self.rect_instances();
+        self.buffers
+            .rect_instances
+            .write(device, queue, &rect_instances);
+
+        let rect_stroke_instances = self.rect_stroke_instances();
+        self.buffers
+            .rect_stroke_instances
+            .write(device, queue, &rect_stroke_instances);
+
+        self.retained.update(device, queue, &self.atlas);
+        self.update_oval_buffers(device, queue);
+
+        let image_instances = self.image_instances();
+        self.buffers
+            .image_instances
+            .write(device, queue, &image_instances);
+
+        let meshes = self.generate_meshes();
+        let translucent = self.translucent_shapes(&meshes);
+
+        let buffers = &mut self.buffers;
+
+        let mesh_ranges = Self::write_mesh_buffer(
+            &mut buffers.meshes,
+            &meshes.fills,
+            Instance::is_translucent,
+            &translucent.fill_meshes,
+            device,
+            queue,
+        );
+        let stroke_mesh_ranges = Self::write_mesh_buffer(
+            &mut buffers.stroke_meshes,
+            &meshes.strokes,
+            StrokeInstance::is_translucent,
+            &translucent.stroke_meshes,
+            device,
+            queue,
+        );
+        let texture_mesh_ranges = Self::write_mesh_buffer(
+            &mut buffers.texture_meshes,
+            &meshes.textured,
+            TextureInstance::is_translucent,
+            &translucent.texture_meshes,
+            device,
+            queue,
+        );
+
+        buffers
+            .translucent
+            .shape_instances
+            .write(device, queue, &translucent.shape_instances);
+        buffers
+            .translucent
+            .stroke_instances
+            .write(device, queue, &translucent.stroke_instances);
+        buffers
+            .translucent
+            .image_instances
+            .write(device, queue, &translucent.image_instances);
+        buffers
+            .translucent
+            .sdf_instances
+            .write(device, queue, &translucent.sdf_instances);
+
+        FrameData {
+            meshes: mesh_ranges,
+            stroke_meshes: stroke_mesh_ranges,
+            texture_meshes: texture_mesh_ranges,
+            translucent_batches: translucent.batches,
+        }
+    }
+
+    /// adds a [Rect] which stays until it gets [removed](ShapeRenderer::remove)
+    ///
+    /// does not get cleared by [clear](ShapeRenderer::clear), can be changed later with [rect_mut](ShapeRenderer::rect_mut)
+    pub fn add_rect(&mut self) -> (ShapeHandle, &mut Rect) {
+        match self.retained.insert(RetainedShape::Rect(Rect::default())) {
+            (handle, RetainedShape::Rect(rect)) => (handle, rect),
+            _ => unreachable!(),
+        }
+    }
+
+    /// adds an [Oval] which stays until it gets [removed](ShapeRenderer::remove)
+    ///
+    /// does not get cleared by [clear](ShapeRenderer::clear), can be changed later with [oval_mut](ShapeRenderer::oval_mut)
+    pub fn add_oval(&mut self) -> (ShapeHandle, &mut Oval) {
+        match self.retained.insert(RetainedShape::Oval(Oval::default())) {
+            (handle, RetainedShape::Oval(oval)) => (handle, oval),
+            _ => unreachable!(),
+        }
+    }
+
+    /// adds an [Image] which stays until it gets [removed](ShapeRenderer::remove)
+    ///
+    /// does not get cleared by [clear](ShapeRenderer::clear), can be changed later with [image_mut](ShapeRenderer::image_mut)
+    ///
+    /// fails if the texture was added to another renderer or got removed
+    pub fn add_image(&mut self, texture: TextureId) -> Result<(ShapeHandle, &mut Image), Error> {
+        let image = self.new_image(texture)?;
+
+        match self.retained.insert(RetainedShape::Image(image)) {
+            (handle, RetainedShape::Image(image)) => Ok((handle, image)),
+            _ => unreachable!(),
+        }
+    }
+
+    /// the [Rect] of the handle, it gets uploaded again before the next frame
+    ///
+    /// None -> the shape was removed or is not a [Rect]
+    pub fn rect_mut(&mut self, handle: ShapeHandle) -> Option<&mut Rect> {
+        match self.retained.get_mut(handle)? {
+            RetainedShape::Rect(rect) => Some(rect),
+            _ => None,
+        }
+    }
+
+    /// the [Oval] of the handle, it gets uploaded again before the next frame
+    ///
+    /// None -> the shape was removed or is not an [Oval]
+    pub fn oval_mut(&mut self, handle: ShapeHandle) -> Option<&mut Oval> {
+        match self.retained.get_mut(handle)? {
+            RetainedShape::Oval(oval) => Some(oval),
+            _ => None,
+        }
+    }
+
+    /// the [Image] of the handle, it gets uploaded again before the next frame
+    ///
+    /// None -> the shape was removed or is not an [Image]
+    pub fn image_mut(&mut self, handle: ShapeHandle) -> Option<&mut Image> {
+        match self.retained.get_mut(handle)? {
+            RetainedShape::Image(image) => Some(image),
+            _ => None,
+        }
+    }
+
+    /// hides or shows the shape of the handle, a hidden shape keeps its handle
+    pub fn set_visible(&mut self, handle: ShapeHandle, visible: bool) -> &mut Self {
+        self.retained.set_visible(handle, visible);
+        self
+    }
+
+    /// if the shape of the handle exists and is not hidden
+    pub fn is_visible(&self, handle: ShapeHandle) -> bool {
+        self.retained.is_visible(handle)
+    }
+
+    /// removes the shape of the handle
+    ///
+    /// false -> the shape was already removed
+    pub fn remove(&mut self, handle: ShapeHandle) -> bool {
+        self.retained.remove(handle)
+    }
+
+    /// if the shape of the handle was not removed
+    pub fn contains(&self, handle: ShapeHandle) -> bool {
+        self.retained.contains(handle)
+    }
+
+    /// adds a [Group] inside of the parent, shapes get moved into it with [set_parent](ShapeRenderer::set_parent)
+    ///
+    /// None or a removed parent -> the group is not inside of another group
+    pub fn add_group(&mut self, parent: Option<GroupHandle>) -> (GroupHandle, &mut Group) {
+        self.retained.insert_group(parent)
+    }
+
+    /// the [Group] of the handle, every shape inside of it gets uploaded again before the next frame
+    pub fn group_mut(&mut self, handle: GroupHandle) -> Option<&mut Group> {
+        self.retained.group_mut(handle)
+    }
+
+    /// removes the group together with every group and shape inside of it
+    ///
+    /// false -> the group was already removed
+    pub fn remove_group(&mut self, handle: GroupHandle) -> bool {
+        self.retained.remove_group(handle)
+    }
+
+    /// if the group of the handle was not removed
+    pub fn contains_group(&self, handle: GroupHandle) -> bool {
+        self.retained.contains_group(handle)
+    }
+
+    /// moves the shape into the group, its position, rotation, scale and layer are relative to the group from then on
+    ///
+    /// None -> the shape is not inside of a group, false -> the shape or the group was removed
+    pub fn set_parent(&mut self, shape: ShapeHandle, group: Option<GroupHandle>) -> bool {
+        self.retained.set_parent(shape, group)
+    }
+
+    /// removes every shape and group added with [add_rect](ShapeRenderer::add_rect), [add_group](ShapeRenderer::add_group), ...
+    pub fn clear_retained(&mut self) {
+        self.retained.clear();
+    }
+
+    /// clears the current drawn [BasicShapes](BasicShape) which can be added with [ShapeRenderer::rect], [ShapeRenderer::oval], ...
+    ///
+    /// retained shapes stay, see [clear_retained](ShapeRenderer::clear_retained)
+    pub fn clear(&mut self) {
+        self.recs.clear();
+        self.ovals.clear();
+        self.images.clear();
+        self.lines.clear();
+        self.polygons.clear();
+        self.paths.clear();
+        self.rounded_rects.clear();
+        self.arcs.clear();
+        self.pies.clear();
+        self.rings.clear();
+    }
+
+    /// sets the current [frame_size](ShapeRenderer::frame_size)
+    pub fn set_frame_size(&mut self, frame_size: (f32, f32)) -> &mut Self {
+        self.frame_size = frame_size;
+        self
+    }
+
+    /// frame_size.0 is the with in which [BasicShape] get displayed.
+    ///
+    /// frame_size.1 is the height in which [BasicShape] get displayed
+    ///
+    /// (0,0) -> center of screen
+    pub fn frame_size(&self) -> (f32, f32) {
+        self.frame_size
+    }
+
+    /// sets the current [frame_offset](ShapeRenderer::frame_offset)
+    pub fn set_frame_offset(&mut self, frame_offset: (f32, f32)) -> &mut Self {
+        self.frame_offset = frame_offset;
+        self
+    }
+
+    /// frame_offset are values which get added to all [BasicShape]
+    pub fn frame_offset(&self) -> (f32, f32) {
+        self.frame_offset
+    }
+
+    /// sets the current [camera](ShapeRenderer::camera)
+    pub fn set_camera(&mut self, camera: Option<Camera2D>) -> &mut Self {
+        self.camera = camera;
+        self
+    }
+
+    /// the [Camera2D] the shapes get viewed through
+    ///
+    /// None -> the [frame_size](ShapeRenderer::frame_size) and [frame_offset](ShapeRenderer::frame_offset) get used
+    pub fn camera(&self) -> Option<&Camera2D> {
+        self.camera.as_ref()
+    }
+
+    /// world -> clip space of the camera or the frame
+    fn view_matrix(&self) -> ViewMatrix {
+        match &self.camera {
+            Some(camera) => camera.view_matrix(),
+            None => frame_view_matrix(self.frame_size, self.frame_offset),
+        }
+    }
+
+    /// the topmost shape whose geometry contains the point in the world
+    ///
+    /// higher layers are in front, on the same layer the shape which gets drawn on top.
+    /// shapes without fill only get hit on their stroke, screen space shapes where they are on the screen.
+    ///
+    /// [Line], [Polygon], [PathShape], [RoundedRect], [Arc], [Pie] and [Ring] get hit on the triangles of their meshes,
+    /// also in the [Sdf](RenderMode::Sdf) mode where their curves are drawn smoother than that
+    pub fn shape_at(&self, point: (f32, f32)) -> Option<ShapeId> {
+        self.hit_tester().shape_at(&self.hit_shapes(), point)
+    }
+
+    /// every shape which intersects the area between the two corners in the world
+    ///
+    /// sorted like [shape_at](ShapeRenderer::shape_at) -> topmost first
+    pub fn shapes_in(&self, corner: (f32, f32), opposite_corner: (f32, f32)) -> Vec<ShapeId> {
+        self.hit_tester()
+            .shapes_in(&self.hit_shapes(), corner, opposite_corner)
+    }
+
+    /// sets the current [image_hit_alpha](ShapeRenderer::image_hit_alpha)
+    pub fn set_image_hit_alpha(&mut self, image_hit_alpha: Option<f32>) -> &mut Self {
+        self.image_hit_alpha = image_hit_alpha;
+        self
+    }
+
+    /// minimal alpha of the texture of an [Image] for [shape_at](ShapeRenderer::shape_at) to hit it
+    ///
+    /// None -> the whole image gets hit, also if the [texture copies](ShapeRenderer::keep_texture_copies) were dropped
+    pub fn image_hit_alpha(&self) -> Option<f32> {
+        self.image_hit_alpha
+    }
+
+    fn hit_tester(&self) -> HitTester<'_> {
+        HitTester {
+            view: self.view_matrix(),
+            surface_size: self.surface_size,
+            atlas: self.atlas.pages(),
+            image_alpha: self.image_hit_alpha,
+        }
+    }
+
+    /// every shape which can be hit in draw order
+    fn hit_shapes(&self) -> Vec<HitShape> {
+        let mut shapes: Vec<_> = self
+            .recs
+            .iter()
+            .enumerate()
+            .map(|(index, rect)| HitShape::rect(ShapeId::Rect(index), &rect.data))
+            .collect();
+
+        let mut ovals: Vec<_> = self
+            .ovals
+            .iter()
+            .enumerate()
+            .map(|(index, oval)| HitShape::oval(ShapeId::Oval(index), &oval.data))
+            .collect();
+
+        let mut images: Vec<_> = self
+            .images
+            .iter()
+            .enumerate()
+            .filter_map(|(index, image)| HitShape::image(ShapeId::Image(index), image, &self.atlas))
+            .collect();
+
+        // retained shapes get drawn after the others of their kind
+        for (handle, shape) in self.retained.iter() {
+            let id = ShapeId::Retained(handle);
+            match shape.as_ref() {
+                RetainedShape::Rect(rect) => shapes.push(HitShape::rect(id, &rect.data)),
+                RetainedShape::Oval(oval) => ovals.push(HitShape::oval(id, &oval.data)),
+                RetainedShape::Image(image) => {
+                    images.extend(HitShape::image(id, image, &self.atlas))
+                }
+            }
+        }
+
+        shapes.extend(ovals);
+
+        // the generated meshes get drawn after the ovals, rounded images after the images
+        shapes.extend(
+            self.lines
+                .iter()
+                .enumerate()
+                .map(|(index, line)| HitShape::line(ShapeId::Line(index), line)),
+        );
+        shapes.extend(self.paths.iter().enumerate().map(|(index, path)| {
+            let tolerance = self.mesh_tolerance(path.screen_space);
+            HitShape::path(ShapeId::Path(index), path, tolerance)
+        }));
+        shapes.extend(
+            self.polygons
+                .iter()
+                .enumerate()
+                .map(|(index, polygon)| HitShape::polygon(ShapeId::Polygon(index), polygon)),
+        );
+
+        let (rounded_images, rounded_rects): (Vec<_>, Vec<_>) = self
+            .rounded_rects
+            .iter()
+            .enumerate()
+            .partition(|(_, rounded_rect)| rounded_rect.texture.is_some());
+        let rounded_rect = |(index, rounded_rect): (usize, &RoundedRect)| {
+            HitShape::rounded_rect(ShapeId::RoundedRect(index), rounded_rect, &self.atlas)
+        };
+
+        shapes.extend(rounded_rects.into_iter().filter_map(rounded_rect));
+
+        shapes.extend(
+            self.arcs
+                .iter()
+                .enumerate()
+                .map(|(index, arc)| HitShape::arc(ShapeId::Arc(index), &arc.data, &arc.geometry)),
+        );
+        shapes.extend(
+            self.pies
+                .iter()
+                .enumerate()
+                .map(|(index, pie)| HitShape::arc(ShapeId::Pie(index), &pie.data, &pie.geometry)),
+        );
+        shapes.extend(
+            self.rings.iter().enumerate().map(|(index, ring)| {
+                HitShape::arc(ShapeId::Ring(index), &ring.data, &ring.geometry)
+            }),
+        );
+
+        shapes.extend(images);
+        shapes.extend(rounded_images.into_iter().filter_map(rounded_rect));
+        shapes
+    }
+
+    /// resizes the depthBuffer and the multisampled texture should be called on every window resize
+    pub fn resize(&mut self, device: &Device, config: &SurfaceConfiguration) -> &mut Self {
+        self.resize_targets(device, (config.width, config.height));
+        self
+    }
+
+    fn resize_targets(&mut self, device: &Device, size: (u32, u32)) {
+        self.depth_texture =
+            DepthBuffer::create_depth_texture(device, size, self.sample_count, "depth_texture");
+        self.multisample_texture =
+            Self::create_multisample_texture(device, size, self.format, self.sample_count);
+        self.surface_size = size;
+    }
+
+    /// sets the current [path_tolerance](ShapeRenderer::path_tolerance)
+    pub fn set_path_tolerance(&mut self, path_tolerance: f32) -> &mut Self {
+        self.path_tolerance = path_tolerance;
+        self
+    }
+
+    /// max distance in pixels between the curves of a [Path] and the rendered mesh
+    ///
+    /// gets converted into frame units with the current [frame_size](ShapeRenderer::frame_size)
+    /// so zooming in keeps curves smooth
+    pub fn path_tolerance(&self) -> f32 {
+        self.path_tolerance
+    }
+
+    /// sets the current [render_mode](ShapeRenderer::render_mode)
+    pub fn set_render_mode(&mut self, render_mode: RenderMode) -> &mut Self {
+        self.render_mode = render_mode;
+        self.retained.set_sdf(render_mode == RenderMode::Sdf);
+        self
+    }
+
+    /// how [Rect], [Oval], [RoundedRect] and straight [Line] shapes get drawn
+    ///
+    /// default -> [RenderMode::Tessellated]
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+
+    /// samples per pixel used for anti-aliasing, set with [ShapeRenderer::with_sample_count]
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// sets the clearColor/ backgroundColor
+    pub fn background_color(&mut self, background_color: Color) -> &mut Self {
+        self.background_color = background_color;
+        self
+    }
+
+    /// renders [Rect] and returns a Ref to it
+    pub fn rect(&mut self) -> &mut Rect {
+        self.recs.push(Rect::default());
+        self.recs.last_mut().unwrap()
+    }
+
+    fn rect_instances(&self) -> Vec<Instance> {
+        self.tessellated_recs()
+            .filter(|rect| rect.data.fill)
+            .map(|rect| rect.to_instance())
+            .filter(|instance| !instance.is_translucent())
+            .collect()
+    }
+
+    fn rect_stroke_instances(&self) -> Vec<StrokeInstance> {
+        self.tessellated_recs()
+            .filter_map(|rect| rect.to_stroke_instance())
+            .filter(|instance| !instance.is_translucent())
+            .collect()
+    }
+
+    /// [Rect] shapes which do not get drawn in [RenderMode::Sdf]
+    fn tessellated_recs(&self) -> impl Iterator<Item = &Rect> {
+        let sdf = self.render_mode == RenderMode::Sdf;
+        self.recs.iter().filter(move |_| !sdf)
+    }
+
+    /// [Oval] shapes which do not get drawn in [RenderMode::Sdf]
+    fn tessellated_ovals(&self) -> impl Iterator<Item = &Oval> {
+        let sdf = self.render_mode == RenderMode::Sdf;
+        self.ovals.iter().filter(move |_| !sdf)
+    }
+
+    /// renders [Oval] and returns a Ref to it
+    pub fn oval(&mut self) -> &mut Oval {
+        self.ovals.push(Oval::default());
+        self.ovals.last_mut().unwrap()
+    }
+
+    /// writes the opaque instances of every used segment count, the meshes of new segment counts get created once
+    fn update_oval_buffers(&mut self, device: &Device, queue: &Queue) {
+        let mut ovals = HashMap::<u32, (Vec<Instance>, Vec<StrokeInstance>)>::new();
+
+        for oval in self.tessellated_ovals() {
+            let (fills, strokes) = ovals.entry(oval.detail).or_default();
+
+            if oval.data.fill {
+                fills.push(oval.to_instance());
+            }
+            strokes.extend(oval.to_stroke_instance());
+        }
+
+        // unused segment counts keep their mesh for later frames
+        for buffers in self.buffers.ovals.values_mut() {
+            buffers.fill.2.write::<Instance>(device, queue, &[]);
+            buffers.stroke.2.write::<StrokeInstance>(device, queue, &[]);
+        }
+
+        for (detail, (mut fills, mut strokes)) in ovals {
+            fills.retain(|instance| !instance.is_translucent());
+            strokes.retain(|instance| !instance.is_translucent());
+
+            let buffers = self
+                .buffers
+                .ovals
+                .entry(detail)
+                .or_insert_with(|| OvalBuffers::new(device, detail));
+
+            buffers.fill.2.write(device, queue, &fills);
+            buffers.stroke.2.write(device, queue, &strokes);
+        }
+
+        // retained ovals get drawn with the same meshes
+        for detail in self.retained.oval_details() {
+            self.buffers
+                .ovals
+                .entry(detail)
+                .or_insert_with(|| OvalBuffers::new(device, detail));
+        }
+    }
+
+    /// renders [Image] and returns a Ref to it
+    ///
+    /// fails if the texture was added to another renderer or got removed
+    pub fn image(&mut self, texture: TextureId) -> Result<&mut Image, Error> {
+        let image = self.new_image(texture)?;
+
+        self.images.push(image);
+        Ok(self.images.last_mut().unwrap())
+    }
+
+    fn new_image(&self, texture: TextureId) -> Result<Image, Error> {
+        self.atlas.check(texture)?;
+
+        Ok(Image {
+            texture: Some(texture),
+            ..Default::default()
+        })
+    }
+
+    fn image_instances(&self) -> Vec<TextureInstance> {
+        self.images
+            .iter()
+            .filter_map(|image| image.to_instance(&self.atlas))
+            .filter(|instance| !instance.is_translucent())
+            .collect()
+    }
+
+    /// renders a straight [Line] and returns a Ref to it
+    pub fn line(&mut self, from: (f32, f32), to: (f32, f32)) -> &mut Line {
+        self.lines.push(Line::new(vec![from, to]));
+        self.lines.last_mut().unwrap()
+    }
+
+    /// renders a [Line] through all points and returns a Ref to it
+    pub fn polyline(&mut self, points: &[(f32, f32)]) -> &mut Line {
+        self.lines.push(Line::new(points.to_vec()));
+        self.lines.last_mut().unwrap()
+    }
+
+    /// renders [RoundedRect] and returns a Ref to it
+    pub fn rounded_rect(&mut self) -> &mut RoundedRect {
+        self.rounded_rects.push(RoundedRect::default());
+        self.rounded_rects.last_mut().unwrap()
+    }
+
+    /// renders [RoundedRect] filled with a texture and returns a Ref to it
+    ///
+    /// fails if the texture was added to another renderer or got removed
+    pub fn rounded_image(&mut self, texture: TextureId) -> Result<&mut RoundedRect, Error> {
+        self.atlas.check(texture)?;
+
+        let rounded_rect = RoundedRect {
+            texture: Some(texture),
+            ..Default::default()
+        };
+
+        self.rounded_rects.push(rounded_rect);
+        Ok(self.rounded_rects.last_mut().unwrap())
+    }
+
+    /// renders [Arc] and returns a Ref to it
+    pub fn arc(&mut self) -> &mut Arc {
+        self.arcs.push(Arc::default());
+        self.arcs.last_mut().unwrap()
+    }
+
+    /// renders [Pie] and returns a Ref to it
+    pub fn pie(&mut self) -> &mut Pie {
+        self.pies.push(Pie::default());
+        self.pies.last_mut().unwrap()
+    }
+
+    /// renders [Ring] and returns a Ref to it
+    pub fn ring(&mut self) -> &mut Ring {
+        self.rings.push(Ring::default());
+        self.rings.last_mut().unwrap()
+    }
+
+    /// all [Arc], [Pie] and [Ring] shapes
+    fn arc_shapes(&self) -> impl Iterator<Item = (&BasicShapeData, &ArcGeometry)> {
+        self.arcs
+            .iter()
+            .map(|arc| (&arc.data, &arc.geometry))
+            .chain(self.pies.iter().map(|pie| (&pie.data, &pie.geometry)))
+            .chain(self.rings.iter().map(|ring| (&ring.data, &ring.geometry)))
+    }
+
+    /// renders a [Polygon] through the points of the outline and returns a Ref to it
+    pub fn polygon(&mut self, outline: &[(f32, f32)]) -> &mut Polygon {
+        self.polygons.push(Polygon::new(outline));
+        self.polygons.last_mut().unwrap()
+    }
+
+    /// renders the inside of the [Path] and returns a Ref to it
+    ///
+    /// sub paths inside of other sub paths become holes
+    pub fn fill_path(&mut self, path: &Path) -> &mut PathShape {
+        self.paths
+            .push(PathShape::new(path.clone(), PathStyle::Fill));
+        self.paths.last_mut().unwrap()
+    }
+
+    /// renders a line along the [Path] and returns a Ref to it
+    pub fn stroke_path(&mut self, path: &Path) -> &mut PathShape {
+        self.paths
+            .push(PathShape::new(path.clone(), PathStyle::Stroke));
+        self.paths.last_mut().unwrap()
+    }
+
+    /// size of one pixel in frame units
+    fn pixel_size(&self) -> f32 {
+        if let Some(camera) = &self.camera {
+            return camera.pixel_size(self.surface_size);
+        }
+
+        let width = self.frame_size.0 / self.surface_size.0.max(1) as f32;
+        let height = self.frame_size.1 / self.surface_size.1.max(1) as f32;
+
+        width.abs().min(height.abs())
+    }
+
+    /// max distance in frame units between the curves of a [Path] and its mesh
+    ///
+    /// screen space paths are measured in pixels
+    fn mesh_tolerance(&self, screen_space: bool) -> f32 {
+        if screen_space {
+            self.path_tolerance
+        } else {
+            self.path_tolerance * self.pixel_size()
+        }
+    }
+
+    /// tessellates every shape which is not drawn with a shared mesh
+    fn generate_meshes(&self) -> FrameMeshes {
+        let sdf = self.render_mode == RenderMode::Sdf;
+
+        let mut fills: Vec<_> = self
+            .lines
+            .iter()
+            .filter(|line| !sdf || line.to_sdf_instance().is_none())
+            .map(|line| (line.to_mesh(), line.to_instance()))
+            .collect();
+
+        fills.extend(self.paths.iter().map(|path| {
+            (
+                path.to_mesh(self.mesh_tolerance(path.screen_space)),
+                path.to_instance(),
+            )
+        }));
+
+        fills.extend(
+            self.polygons
+                .iter()
+                .filter(|polygon| polygon.data.fill)
+                .map(|polygon| (polygon.to_mesh(), polygon.to_instance())),
+        );
+
+        fills.extend(
+            self.rounded_rects
+                .iter()
+                .filter(|rounded_rect| {
+                    !sdf && rounded_rect.data.fill && rounded_rect.texture.is_none()
+                })
+                .map(|rounded_rect| (rounded_rect.to_mesh(), rounded_rect.to_instance())),
+        );
+
+        fills.extend(
+            self.arc_shapes()
+                .filter(|(data, _)| data.fill)
+                .map(|(data, geometry)| (geometry.to_mesh(), data.into())),
+        );
+
+        let mut strokes: Vec<_> = self
+            .polygons
+            .iter()
+            .filter_map(|polygon| Some((polygon.to_stroke_mesh(), polygon.to_stroke_instance()?)))
+            .collect();
+
+        strokes.extend(
+            self.rounded_rects
+                .iter()
+                .filter(|_| !sdf)
+                .filter_map(|rounded_rect| {
+                    Some((
+                        rounded_rect.to_stroke_mesh(),
+                        rounded_rect.to_stroke_instance()?,
+                    ))
+                }),
+        );
+
+        strokes.extend(self.arc_shapes().filter_map(|(data, geometry)| {
+            let instance: Option<StrokeInstance> = data.into();
+            Some((geometry.to_stroke_mesh(), instance?))
+        }));
+
+        let textured = self
+            .rounded_rects
+            .iter()
+            .filter(|rounded_rect| rounded_rect.data.fill)
+            .filter_map(|rounded_rect| {
+                Some((
+                    rounded_rect.to_mesh(),
+                    rounded_rect.to_texture_instance(&self.atlas)?,
+                ))
+            })
+            .collect();
+
+        FrameMeshes {
+            fills,
+            strokes,
+            textured,
+        }
+        .without_empty()
+    }
+
+    /// combines all meshes into one buffer, every vertex gets a copy of the instance of its mesh
+    ///
+    /// the opaque meshes come first, the translucent ones follow in the order they get drawn
+    fn write_mesh_buffer<V: Pod, I: Pod>(
+        buffers: &mut MeshBuffers,
+        meshes: &[(Mesh<V>, I)],
+        is_translucent: fn(&I) -> bool,
+        translucent: &[usize],
         device: &Device,
         queue: &Queue,
-    ) -> &mut Self {
-        for bytes in bytes {
-            self.textures.push(image::load_from_memory(bytes).unwrap());
+    ) -> MeshRanges {
+        let mut vertices = vec![];
+        let mut instances = vec![];
+        let mut indices = vec![];
+
+        // returns the end of the indices of the mesh
+        let mut append = |(mesh, instance): &(Mesh<V>, I)| {
+            let offset = vertices.len() as i32;
+
+            vertices.extend_from_slice(&mesh.vertices);
+            instances.extend(std::iter::repeat(*instance).take(mesh.vertices.len()));
+            indices.extend(mesh.indices.iter().map(|index| index + offset));
+
+            indices.len() as u32
+        };
+
+        let mut opaque_end = 0;
+        for mesh in meshes
+            .iter()
+            .filter(|(_, instance)| !is_translucent(instance))
+        {
+            opaque_end = append(mesh);
         }
 
-        self.upload_textures(device, queue);
+        let mut translucent_starts = vec![opaque_end];
+        for index in translucent {
+            translucent_starts.push(append(&meshes[*index]));
+        }
 
-        self
+        buffers.vertex_buffer.write(device, queue, &vertices);
+        buffers.indices_buffer.write(device, queue, &indices);
+        buffers.instance_buffer.write(device, queue, &instances);
+
+        MeshRanges {
+            opaque: 0..opaque_end,
+            translucent: translucent_starts,
+        }
     }
 
-    /// adds multiple textures to the usable texturePool
-    fn upload_textures(&mut self, device: &Device, queue: &Queue) {
-        let mut rects_to_place: GroupedRectsToPlace<usize, usize> = GroupedRectsToPlace::new();
+    /// collects all translucent shapes sorted by their layer and batches neighbours of the same kind
+    ///
+    /// strokes get drawn after the fills of the same layer
+    fn translucent_shapes(&self, meshes: &FrameMeshes) -> TranslucentShapes {
+        let mut shapes: Vec<(u32, ShapeKind, TranslucentInstance)> = vec![];
+
+        let mut push_basic_shape = |data: &BasicShapeData, fill_kind, stroke_kind| {
+            let instance = Instance::from(data);
+            if data.fill && instance.is_translucent() {
+                shapes.push((
+                    instance.layer * 2,
+                    fill_kind,
+                    TranslucentInstance::Shape(instance),
+                ));
+            }
 
-        for (index, image) in self.textures.iter().enumerate() {
-            let dimensions = image.dimensions();
+            if let Some(instance) = Option::<StrokeInstance>::from(data) {
+                if instance.is_translucent() {
+                    shapes.push((
+                        instance.layer * 2 + 1,
+                        stroke_kind,
+                        TranslucentInstance::Stroke(instance),
+                    ));
+                }
+            }
+        };
 
-            rects_to_place.push_rect(
-                index,
-                None,
-                RectToInsert::new(dimensions.0, dimensions.1, 1),
+        for rect in self.tessellated_recs() {
+            push_basic_shape(&rect.data, ShapeKind::Rect, ShapeKind::RectStroke);
+        }
+        for rect in self.retained.translucent_rects() {
+            push_basic_shape(&rect.data, ShapeKind::Rect, ShapeKind::RectStroke);
+        }
+
+        for oval in self.tessellated_ovals() {
+            let detail = oval.detail;
+            push_basic_shape(
+                &oval.data,
+                ShapeKind::Oval(detail),
+                ShapeKind::OvalStroke(detail),
+            );
+        }
+        for oval in self.retained.translucent_ovals() {
+            let detail = oval.detail;
+            push_basic_shape(
+                &oval.data,
+                ShapeKind::Oval(detail),
+                ShapeKind::OvalStroke(detail),
             );
         }
 
-        let mut target_bins = BTreeMap::new();
-        target_bins.insert(0, TargetBin::new(self.texture_size, self.texture_size, 1));
+        shapes.extend(
+            meshes
+                .fills
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, instance))| instance.is_translucent())
+                .map(|(index, (_, instance))| {
+                    (
+                        instance.layer * 2,
+                        ShapeKind::Mesh,
+                        TranslucentInstance::Mesh(index),
+                    )
+                }),
+        );
+
+        shapes.extend(
+            meshes
+                .textured
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, instance))| instance.is_translucent())
+                .map(|(index, (_, instance))| {
+                    (
+                        instance.layer * 2,
+                        ShapeKind::TextureMesh,
+                        TranslucentInstance::TextureMesh(index),
+                    )
+                }),
+        );
+
+        shapes.extend(
+            meshes
+                .strokes
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, instance))| instance.is_translucent())
+                .map(|(index, (_, instance))| {
+                    (
+                        instance.layer * 2 + 1,
+                        ShapeKind::StrokeMesh,
+                        TranslucentInstance::StrokeMesh(index),
+                    )
+                }),
+        );
 
-        let rectangle_placements = pack_rects(
-            &rects_to_place,
-            &mut target_bins,
-            &volume_heuristic,
-            &contains_smallest_box,
+        shapes.extend(
+            self.images
+                .iter()
+                .chain(self.retained.translucent_images())
+                .filter_map(|image| image.to_instance(&self.atlas))
+                .filter(|instance| instance.is_translucent())
+                .map(|instance| {
+                    (
+                        instance.layer * 2,
+                        ShapeKind::Image,
+                        TranslucentInstance::Image(instance),
+                    )
+                }),
         );
 
-        let rectangle_placements = match rectangle_placements {
-            Ok(rectangle_pack) => rectangle_pack,
-            Err(_) => {
-                self.texture_size *= 2;
-                self.upload_textures(device, queue);
+        if self.render_mode == RenderMode::Sdf {
+            shapes.extend(self.sdf_instances().into_iter().map(|(order, instance)| {
+                (order, ShapeKind::Sdf, TranslucentInstance::Sdf(instance))
+            }));
+        }
+
+        // stable -> shapes on the same layer keep their draw order
+        shapes.sort_by_key(|(order, _, _)| *order);
+
+        let mut shape_instances = vec![];
+        let mut stroke_instances = vec![];
+        let mut image_instances = vec![];
+        let mut sdf_instances = vec![];
+        let mut fill_meshes = vec![];
+        let mut stroke_meshes = vec![];
+        let mut texture_meshes = vec![];
+        let mut batches: Vec<TranslucentBatch> = vec![];
+
+        for (_, kind, instance) in shapes {
+            let index = match instance {
+                TranslucentInstance::Shape(instance) => {
+                    shape_instances.push(instance);
+                    shape_instances.len() as u32 - 1
+                }
+                TranslucentInstance::Stroke(instance) => {
+                    stroke_instances.push(instance);
+                    stroke_instances.len() as u32 - 1
+                }
+                TranslucentInstance::Image(instance) => {
+                    image_instances.push(instance);
+                    image_instances.len() as u32 - 1
+                }
+                TranslucentInstance::Sdf(instance) => {
+                    sdf_instances.push(instance);
+                    sdf_instances.len() as u32 - 1
+                }
+                TranslucentInstance::Mesh(mesh) => {
+                    fill_meshes.push(mesh);
+                    fill_meshes.len() as u32 - 1
+                }
+                TranslucentInstance::StrokeMesh(mesh) => {
+                    stroke_meshes.push(mesh);
+                    stroke_meshes.len() as u32 - 1
+                }
+                TranslucentInstance::TextureMesh(mesh) => {
+                    texture_meshes.push(mesh);
+                    texture_meshes.len() as u32 - 1
+                }
+            };
+
+            match batches.last_mut() {
+                Some(batch) if batch.kind == kind => batch.instances.end = index + 1,
+                _ => batches.push(TranslucentBatch {
+                    kind,
+                    instances: index..index + 1,
+                }),
+            }
+        }
+
+        TranslucentShapes {
+            shape_instances,
+            stroke_instances,
+            image_instances,
+            sdf_instances,
+            fill_meshes,
+            stroke_meshes,
+            texture_meshes,
+            batches,
+        }
+    }
+
+    /// every shape drawn with the distance shader and its sort order
+    fn sdf_instances(&self) -> Vec<(u32, SdfInstance)> {
+        let mut instances: Vec<_> = self
+            .recs
+            .iter()
+            .chain(self.retained.sdf_rects())
+            .map(|rect| rect.data.to_sdf_instance(SdfKind::Box, [0.0; 4]))
+            .chain(
+                self.ovals
+                    .iter()
+                    .chain(self.retained.sdf_ovals())
+                    .map(|oval| oval.data.to_sdf_instance(SdfKind::Oval, [0.0; 4])),
+            )
+            .chain(self.rounded_rects.iter().map(|rounded_rect| {
+                let mut instance = rounded_rect
+                    .data
+                    .to_sdf_instance(SdfKind::Box, rounded_rect.radii);
+
+                // the texture gets drawn as a mesh, only the stroke is left
+                if rounded_rect.texture.is_some() {
+                    instance.color[3] = 0.0;
+                }
+                instance
+            }))
+            .chain(self.lines.iter().filter_map(|line| line.to_sdf_instance()))
+            .filter(|instance| {
+                instance.color[3] > 0.0
+                    || (instance.stroke_width > 0.0 && instance.stroke_color[3] > 0.0)
+            })
+            .map(|instance| (instance.layer * 2, instance))
+            .collect();
 
-                return;
+        // strokes around textures have to be drawn after them
+        for (order, instance) in &mut instances {
+            if instance.color[3] <= 0.0 {
+                *order += 1;
             }
-        };
+        }
 
-        self.textures_cords.clear();
-        let mut buffer = ImageBuffer::new(self.texture_size, self.texture_size);
-
-        for (index, (_, location)) in rectangle_placements.packed_locations() {
-            buffer
-                .copy_from(
-                    self.textures.get(*index).unwrap(),
-                    location.x(),
-                    location.y(),
-                )
-                .expect("TODO: panic message");
-
-            self.textures_cords.push((
-                (
-                    location.x() as f32 / self.texture_size as f32,
-                    location.y() as f32 / self.texture_size as f32,
-                ),
-                (
-                    location.width() as f32 / self.texture_size as f32,
-                    location.height() as f32 / self.texture_size as f32,
-                ),
-            ))
+        instances
+    }
+
+    /// adds a texture which can be drawn with [image](ShapeRenderer::image)
+    ///
+    /// fails if the bytes are not an image
+    pub fn add_texture_from_bytes(
+        &mut self,
+        bytes: &[u8],
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<TextureId, Error> {
+        let textures = self.add_textures_from_bytes(&[bytes], device, queue)?;
+        Ok(textures[0])
+    }
+
+    /// adds multiple textures at once so the atlas only gets packed and uploaded once
+    ///
+    /// if one of them fails nothing gets added
+    pub fn add_textures_from_bytes(
+        &mut self,
+        bytes: &[&[u8]],
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<Vec<TextureId>, Error> {
+        let textures = self.atlas.add_from_bytes(bytes)?;
+
+        self.upload_textures(device, queue);
+
+        Ok(textures)
+    }
+
+    /// sets the current [max_atlas_size](ShapeRenderer::max_atlas_size)
+    ///
+    /// gets clamped to the limit of the device, is used the next time textures get added
+    pub fn set_max_atlas_size(&mut self, max_atlas_size: u32) -> &mut Self {
+        self.atlas.set_max_size(max_atlas_size);
+        self
+    }
+
+    /// biggest width and height of a page of the texture atlas
+    ///
+    /// textures which do not fit onto one page anymore get put onto another one,
+    /// defaults to the max texture size of the device
+    pub fn max_atlas_size(&self) -> u32 {
+        self.atlas.max_size()
+    }
+
+    /// sets the current [atlas_padding](ShapeRenderer::atlas_padding)
+    ///
+    /// is used the next time textures get added, all textures get packed again then
+    pub fn set_atlas_padding(&mut self, atlas_padding: u32) -> &mut Self {
+        self.atlas.set_padding(atlas_padding);
+        self
+    }
+
+    /// pixels around every texture in the atlas which repeat its edge
+    ///
+    /// keeps neighbouring textures from bleeding into each other at the borders, defaults to 1
+    pub fn atlas_padding(&self) -> u32 {
+        self.atlas.padding()
+    }
+
+    /// removes the texture, retained [Image]s of it are not drawn anymore
+    ///
+    /// its space in the atlas gets reused after [compact_textures](ShapeRenderer::compact_textures)
+    /// or when added textures do not fit anymore.
+    /// false if the texture is from another renderer or was already removed
+    pub fn remove_texture(&mut self, texture: TextureId) -> bool {
+        if !self.atlas.remove(texture) {
+            return false;
+        }
+
+        self.retained.update_textures();
+        true
+    }
+
+    /// packs the remaining textures again so the space of removed ones is free, the atlas may get smaller
+    ///
+    /// every [TextureId] stays valid,
+    /// fails if a larger [atlas_padding](ShapeRenderer::atlas_padding) does not fit anymore
+    pub fn compact_textures(&mut self, device: &Device, queue: &Queue) -> Result<(), Error> {
+        self.atlas.compact()?;
+        self.upload_textures(device, queue);
+        Ok(())
+    }
+
+    /// sets the current [keep_texture_copies](ShapeRenderer::keep_texture_copies)
+    ///
+    /// false drops the copies of the textures which are already uploaded right away
+    pub fn set_keep_texture_copies(&mut self, keep_texture_copies: bool) -> &mut Self {
+        self.atlas.set_keep_copies(keep_texture_copies);
+        self.atlas.uploaded();
+        self
+    }
+
+    /// if the pixels of the textures stay in memory after they got uploaded
+    ///
+    /// without them textures get moved inside of the gpu when the atlas gets packed again
+    /// and [image_hit_alpha](ShapeRenderer::image_hit_alpha) is ignored for them,
+    /// also after the copies are kept again. defaults to true
+    pub fn keep_texture_copies(&self) -> bool {
+        self.atlas.keep_copies()
+    }
+
+    /// uploads what changed in the [TextureAtlas] to the gpu
+    fn upload_textures(&mut self, device: &Device, queue: &Queue) {
+        match (self.atlas.take_update(), &self.atlas_texture) {
+            (None, _) => {}
+            (Some(AtlasUpdate::Placed(textures)), Some(atlas_texture)) => {
+                for texture in textures {
+                    write_padded_texture(queue, atlas_texture, &self.atlas, texture);
+                }
+            }
+            (Some(AtlasUpdate::Placed(_)), None) => self.create_atlas_texture(device, queue, &[]),
+            (Some(AtlasUpdate::Repacked(moved)), _) => {
+                self.create_atlas_texture(device, queue, &moved)
+            }
         }
 
-        let dimensions = buffer.dimensions();
+        self.atlas.uploaded();
+    }
+
+    /// uploads every texture which still has a copy, the others get copied over from the old atlas texture
+    fn create_atlas_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        moved: &[(TextureId, Placement)],
+    ) {
+        self.retained.update_textures();
 
         let texture_size = wgpu::Extent3d {
-            width: dimensions.0,
-            height: dimensions.1,
-            depth_or_array_layers: 1,
+            width: self.atlas.size(),
+            height: self.atlas.size(),
+            // the gl backend can not view a texture with a single layer as an array
+            depth_or_array_layers: self.atlas.page_count().max(2),
         };
 
         let diffuse_texture = device.create_texture(&wgpu::TextureDescriptor {
-            // All textures are stored as 3D, we represent our 2D texture
-            // by setting depth to 1.
+            // every page of the atlas is a layer
             size: texture_size,
-            mip_level_count: 1, // We'll talk about this a little later
+            mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            // Most images are stored using sRGB so we need to reflect that here.
             format: wgpu::TextureFormat::Rgba8Unorm,
-            // TEXTURE_BINDING tells wgpu that we want to use this texture in shaders
-            // COPY_DST means that we want to copy data to this texture
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::COPY_SRC,
             label: Some("diffuse_texture"),
             view_formats: &[],
         });
 
-        queue.write_texture(
-            // Tells wgpu where to copy the pixel data
-            wgpu::ImageCopyTexture {
-                texture: &diffuse_texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            // The actual pixel data
-            &buffer,
-            // The layout of the texture
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: std::num::NonZeroU32::new(4 * dimensions.0),
-                rows_per_image: std::num::NonZeroU32::new(dimensions.1),
+        for texture in self.atlas.with_copies() {
+            write_padded_texture(queue, &diffuse_texture, &self.atlas, texture);
+        }
+
+        if let (Some(old_texture), false) = (&self.atlas_texture, moved.is_empty()) {
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Atlas Move Encoder"),
+            });
+
+            for (texture, from) in moved {
+                let Some((to, copies)) = self.atlas.moves(*texture, *from) else {
+                    continue;
+                };
+
+                for copy in copies {
+                    encoder.copy_texture_to_texture(
+                        atlas_copy_texture(old_texture, from.page, copy.from),
+                        atlas_copy_texture(&diffuse_texture, to.page, copy.to),
+                        wgpu::Extent3d {
+                            width: copy.width,
+                            height: copy.height,
+                            depth_or_array_layers: 1,
+                        },
+                    );
+                }
+            }
+
+            queue.submit(std::iter::once(encoder.finish()));
+        }
+
+        let diffuse_texture_view = diffuse_texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+
+        self.texture_bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.texture_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&diffuse_texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+            label: Some("diffuse_bind_group"),
+        }));
+        self.atlas_texture = Some(diffuse_texture);
+    }
+}
+
+/// gpu buffers which are kept between frames
+struct FrameBuffers {
+    rect_instances: DynamicBuffer,
+    rect_stroke_instances: DynamicBuffer,
+    image_instances: DynamicBuffer,
+    /// meshes of every segment count which got used so far
+    ovals: HashMap<u32, OvalBuffers>,
+    meshes: MeshBuffers,
+    stroke_meshes: MeshBuffers,
+    texture_meshes: MeshBuffers,
+    translucent: TranslucentBuffers,
+}
+
+impl FrameBuffers {
+    fn new(device: &Device) -> Self {
+        FrameBuffers {
+            rect_instances: DynamicBuffer::vertex(device, "Rect InstanceBuffer"),
+            rect_stroke_instances: DynamicBuffer::vertex(device, "Rect Stroke InstanceBuffer"),
+            image_instances: DynamicBuffer::vertex(device, "Image InstanceBuffer"),
+            ovals: HashMap::new(),
+            meshes: MeshBuffers::new(device),
+            stroke_meshes: MeshBuffers::new(device),
+            texture_meshes: MeshBuffers::new(device),
+            translucent: TranslucentBuffers {
+                shape_instances: DynamicBuffer::vertex(device, "Translucent Shape InstanceBuffer"),
+                stroke_instances: DynamicBuffer::vertex(
+                    device,
+                    "Translucent Stroke InstanceBuffer",
+                ),
+                image_instances: DynamicBuffer::vertex(device, "Translucent Image InstanceBuffer"),
+                sdf_instances: DynamicBuffer::vertex(device, "Sdf InstanceBuffer"),
             },
-            texture_size,
-        );
+        }
+    }
+}
+
+/// everything which is only needed for drawing the current frame
+struct FrameData {
+    meshes: MeshRanges,
+    stroke_meshes: MeshRanges,
+    texture_meshes: MeshRanges,
+    translucent_batches: Vec<TranslucentBatch>,
+}
+
+struct InstanceBufferGroup(SimpleBuffer, SimpleBuffer, DynamicBuffer);
+
+struct OvalBuffers {
+    fill: InstanceBufferGroup,
+    stroke: InstanceBufferGroup,
+}
 
-        let diffuse_texture_view =
-            diffuse_texture.create_view(&wgpu::TextureViewDescriptor::default());
+impl OvalBuffers {
+    /// creates the fill and stroke meshes of the segment count
+    fn new(device: &Device, detail: u32) -> Self {
+        let outline = oval_outline(detail);
 
-        self.texture = Some(diffuse_texture_view);
+        let vertices: Vec<_> = outline
+            .iter()
+            .map(|position| OwnVertex {
+                position: *position,
+            })
+            .collect();
+
+        let vertex_buffer = BufferCreator::vertex(device)
+            .label("Oval VertexBuffer")
+            .data(vertices)
+            .build();
+
+        let indices = oval_indices(outline.len());
+
+        let indices_buffer = BufferCreator::indices(device)
+            .label("Oval IndicesBuffer")
+            .data(indices)
+            .build();
+
+        let stroke_mesh = stroke_mesh(&outline);
+
+        let stroke_vertex_buffer = BufferCreator::vertex(device)
+            .label("Oval Stroke VertexBuffer")
+            .data(stroke_mesh.vertices)
+            .build();
+
+        let stroke_indices_buffer = BufferCreator::indices(device)
+            .label("Oval Stroke IndicesBuffer")
+            .data(stroke_mesh.indices)
+            .build();
+
+        OvalBuffers {
+            fill: InstanceBufferGroup(
+                vertex_buffer,
+                indices_buffer,
+                DynamicBuffer::vertex(device, "Oval InstanceBuffer"),
+            ),
+            stroke: InstanceBufferGroup(
+                stroke_vertex_buffer,
+                stroke_indices_buffer,
+                DynamicBuffer::vertex(device, "Oval Stroke InstanceBuffer"),
+            ),
+        }
+    }
+}
+
+/// all shapes with their own geometry
+struct FrameMeshes {
+    fills: Vec<(Mesh, Instance)>,
+    strokes: Vec<(Mesh<StrokeVertex>, StrokeInstance)>,
+    textured: Vec<(Mesh, TextureInstance)>,
+}
+
+impl FrameMeshes {
+    fn without_empty(mut self) -> Self {
+        self.fills.retain(|(mesh, _)| !mesh.indices.is_empty());
+        self.strokes.retain(|(mesh, _)| !mesh.indices.is_empty());
+        self.textured.retain(|(mesh, _)| !mesh.indices.is_empty());
+        self
+    }
+}
+
+/// all cpu generated meshes of a frame
+struct MeshBuffers {
+    vertex_buffer: DynamicBuffer,
+    indices_buffer: DynamicBuffer,
+    /// instance of every vertex
+    instance_buffer: DynamicBuffer,
+}
+
+impl MeshBuffers {
+    fn new(device: &Device) -> Self {
+        MeshBuffers {
+            vertex_buffer: DynamicBuffer::vertex(device, "Mesh VertexBuffer"),
+            indices_buffer: DynamicBuffer::indices(device, "Mesh IndicesBuffer"),
+            instance_buffer: DynamicBuffer::vertex(device, "Mesh InstanceBuffer"),
+        }
     }
 }
 
-struct InstanceBufferGroup(SimpleBuffer, SimpleBuffer, SimpleBuffer);
+/// pipelines of the cpu generated meshes, every vertex carries the instance of its mesh
+struct MeshPipelines {
+    fill: RenderPipeline,
+    stroke: RenderPipeline,
+    texture: RenderPipeline,
+}
+
+impl MeshPipelines {
+    fn new(
+        device: &Device,
+        format: TextureFormat,
+        sample_count: u32,
+        frame_group_layout: &BindGroupLayout,
+        texture_bind_group_layout: &BindGroupLayout,
+        translucent: bool,
+    ) -> Self {
+        let pipeline = |shader_code: &str,
+                        texture: bool,
+                        vertex_layout: wgpu::VertexBufferLayout<'static>,
+                        instance_layout: wgpu::VertexBufferLayout<'static>| {
+            let mut creator = RenderPipelineCreator::from_shader_code(shader_code, device, format)
+                .sample_count(sample_count)
+                .add_bind_group(frame_group_layout);
+
+            if texture {
+                creator = creator.add_bind_group(texture_bind_group_layout);
+            }
+
+            creator = creator
+                .add_vertex_buffer(vertex_layout)
+                .add_vertex_buffer(per_vertex(instance_layout));
+
+            if translucent {
+                creator
+                    .depth_stencil(ShapeRenderer::translucent_depth_stencil())
+                    .blend_state(BlendState::ALPHA_BLENDING)
+                    .build()
+            } else {
+                creator
+                    .depth_stencil(wgpu::DepthStencilState {
+                        format: DepthBuffer::DEPTH_FORMAT,
+                        depth_write_enabled: true,
+                        depth_compare: wgpu::CompareFunction::Less,
+                        stencil: wgpu::StencilState::default(),
+                        bias: wgpu::DepthBiasState::default(),
+                    })
+                    .build()
+            }
+        };
+
+        MeshPipelines {
+            fill: pipeline(
+                include_str!("../resources/shape_shader.wgsl"),
+                false,
+                OwnVertex::descriptor(),
+                Instance::descriptor(),
+            ),
+            stroke: pipeline(
+                include_str!("../resources/stroke_shader.wgsl"),
+                false,
+                StrokeVertex::descriptor(),
+                StrokeInstance::descriptor(),
+            ),
+            texture: pipeline(
+                include_str!("../resources/texture_shader.wgsl"),
+                true,
+                OwnVertex::descriptor(),
+                TextureInstance::descriptor(),
+            ),
+        }
+    }
+}
+
+/// where the meshes of a frame are inside of the [MeshBuffers]
+struct MeshRanges {
+    /// indices of all opaque meshes
+    opaque: Range<u32>,
+    /// first index of every translucent mesh in draw order and the end of the last one
+    translucent: Vec<u32>,
+}
+
+impl MeshRanges {
+    /// indices of the translucent meshes of a batch
+    fn translucent(&self, meshes: &Range<u32>) -> Range<u32> {
+        self.translucent[meshes.start as usize]..self.translucent[meshes.end as usize]
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum ShapeKind {
+    Rect,
+    Oval(u32),
+    RectStroke,
+    OvalStroke(u32),
+    Image,
+    /// cpu generated fills
+    Mesh,
+    /// cpu generated strokes
+    StrokeMesh,
+    /// cpu generated textured fills
+    TextureMesh,
+    /// shapes drawn in [RenderMode::Sdf]
+    Sdf,
+}
+
+enum TranslucentInstance {
+    Shape(Instance),
+    Stroke(StrokeInstance),
+    Image(TextureInstance),
+    Sdf(SdfInstance),
+    /// index into [FrameMeshes::fills]
+    Mesh(usize),
+    /// index into [FrameMeshes::strokes]
+    StrokeMesh(usize),
+    /// index into [FrameMeshes::textured]
+    TextureMesh(usize),
+}
+
+/// consecutive translucent instances which can be drawn with one draw call
+struct TranslucentBatch {
+    kind: ShapeKind,
+    /// meshes in [MeshRanges::translucent] for the mesh kinds
+    instances: Range<u32>,
+}
+
+struct TranslucentBuffers {
+    shape_instances: DynamicBuffer,
+    stroke_instances: DynamicBuffer,
+    image_instances: DynamicBuffer,
+    sdf_instances: DynamicBuffer,
+}
+
+/// translucent instances of a frame in the order they get drawn
+struct TranslucentShapes {
+    shape_instances: Vec<Instance>,
+    stroke_instances: Vec<StrokeInstance>,
+    image_instances: Vec<TextureInstance>,
+    sdf_instances: Vec<SdfInstance>,
+    /// translucent meshes of the [FrameMeshes] in the order they get drawn
+    fill_meshes: Vec<usize>,
+    stroke_meshes: Vec<usize>,
+    texture_meshes: Vec<usize>,
+    batches: Vec<TranslucentBatch>,
+}
+
+/// writes the texture with its padding into its layer of the atlas texture
+fn write_padded_texture(
+    queue: &Queue,
+    atlas_texture: &wgpu::Texture,
+    atlas: &TextureAtlas,
+    texture: TextureId,
+) {
+    let Some((padded, region)) = atlas.padded_texture(texture) else {
+        return;
+    };
+
+    queue.write_texture(
+        atlas_copy_texture(atlas_texture, region.page, (region.x, region.y)),
+        &padded,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(4 * region.width),
+            rows_per_image: std::num::NonZeroU32::new(region.height),
+        },
+        wgpu::Extent3d {
+            width: region.width,
+            height: region.height,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+/// a position in a layer of the atlas texture
+fn atlas_copy_texture(
+    atlas_texture: &wgpu::Texture,
+    page: u32,
+    (x, y): (u32, u32),
+) -> wgpu::ImageCopyTexture<'_> {
+    wgpu::ImageCopyTexture {
+        texture: atlas_texture,
+        mip_level: 0,
+        origin: wgpu::Origin3d { x, y, z: page },
+        aspect: wgpu::TextureAspect::All,
+    }
+}
@@ -0,0 +1,64 @@
+//! 2d affine transformations
+
+/// rows of a 2x3 matrix, x' = m[0][0] * x + m[0][1] * y + m[0][2]
+pub(crate) type Affine = [[f32; 3]; 2];
+
+/// changes nothing
+pub(crate) const IDENTITY: Affine = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+/// scales, then rotates and then moves
+pub(crate) fn from_parts(position: [f32; 2], rotation: f32, scale: [f32; 2]) -> Affine {
+    let (sin, cos) = rotation.sin_cos();
+
+    [
+        [cos * scale[0], -sin * scale[1], position[0]],
+        [sin * scale[0], cos * scale[1], position[1]],
+    ]
+}
+
+pub(crate) fn translation(offset: [f32; 2]) -> Affine {
+    [[1.0, 0.0, offset[0]], [0.0, 1.0, offset[1]]]
+}
+
+/// b gets applied first
+pub(crate) fn multiply(a: &Affine, b: &Affine) -> Affine {
+    let mut result = [[0.0; 3]; 2];
+
+    for row in 0..2 {
+        for column in 0..3 {
+            result[row][column] = a[row][0] * b[0][column] + a[row][1] * b[1][column];
+        }
+        result[row][2] += a[row][2];
+    }
+
+    result
+}
+
+pub(crate) fn apply(m: &Affine, point: [f32; 2]) -> [f32; 2] {
+    [
+        m[0][0] * point[0] + m[0][1] * point[1] + m[0][2],
+        m[1][0] * point[0] + m[1][1] * point[1] + m[1][2],
+    ]
+}
+
+pub(crate) fn determinant(m: &Affine) -> f32 {
+    m[0][0] * m[1][1] - m[0][1] * m[1][0]
+}
+
+/// None if the matrix collapses everything onto a line or a point
+pub(crate) fn invert(m: &Affine) -> Option<Affine> {
+    let determinant = determinant(m);
+    if determinant == 0.0 || !determinant.is_finite() {
+        return None;
+    }
+
+    let [[a, b, x], [c, d, y]] = *m;
+    let (a, b, c, d) = (
+        d / determinant,
+        -b / determinant,
+        -c / determinant,
+        a / determinant,
+    );
+
+    Some([[a, b, -(a * x + b * y)], [c, d, -(c * x + d * y)]])
+}
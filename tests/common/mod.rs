@@ -0,0 +1,335 @@
+//! support for golden image tests
+//!
+//! renders a scene headlessly and compares it against `tests/reference/<name>.png`.
+//! a missing reference fails the test, `UPDATE_REFERENCES=1` writes all of them.
+//! on a mismatch the rendered image and a diff image get written into `target/golden`
+//!
+//! the references always come from the gpu, the [CpuRenderer] only gets compared against them
+
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use image::{Rgba, RgbaImage};
+use wgpu::{Color, Device, Queue, TextureFormat};
+
+use wgpu_shapes::camera::Camera2D;
+use wgpu_shapes::cpu_renderer::CpuRenderer;
+use wgpu_shapes::render::atlas::TextureId;
+use wgpu_shapes::render::offscreen::OffscreenTarget;
+use wgpu_shapes::shape::image::Image;
+use wgpu_shapes::shape::oval::Oval;
+use wgpu_shapes::shape::rect::Rect;
+use wgpu_shapes::shape_renderer::ShapeRenderer;
+
+pub const SIZE: (u32, u32) = (200, 150);
+pub const FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+
+/// how far a rendered image may be off from the reference
+#[derive(Copy, Clone, Debug)]
+pub struct Tolerance {
+    /// max difference of a single channel before the pixel counts as different
+    pub channel: u8,
+    /// count of pixels which may differ, rasterizers do not agree on every edge pixel
+    pub pixels: usize,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            channel: 2,
+            pixels: 8,
+        }
+    }
+}
+
+/// what the [ShapeRenderer] and the [CpuRenderer] have in common so a scene can be drawn by both
+pub trait Scene {
+    fn rect(&mut self) -> &mut Rect;
+    fn oval(&mut self) -> &mut Oval;
+    fn image(&mut self, texture: TextureId) -> &mut Image;
+    fn set_frame_offset(&mut self, frame_offset: (f32, f32));
+    fn set_camera(&mut self, camera: Option<Camera2D>);
+    fn background_color(&mut self, background_color: Color);
+    fn add_textures(&mut self, textures: &[&[u8]]) -> Vec<TextureId>;
+    fn set_max_atlas_size(&mut self, max_atlas_size: u32);
+}
+
+impl Scene for ShapeRenderer {
+    fn rect(&mut self) -> &mut Rect {
+        ShapeRenderer::rect(self)
+    }
+
+    fn oval(&mut self) -> &mut Oval {
+        ShapeRenderer::oval(self)
+    }
+
+    fn image(&mut self, texture: TextureId) -> &mut Image {
+        ShapeRenderer::image(self, texture).unwrap()
+    }
+
+    fn set_frame_offset(&mut self, frame_offset: (f32, f32)) {
+        ShapeRenderer::set_frame_offset(self, frame_offset);
+    }
+
+    fn set_camera(&mut self, camera: Option<Camera2D>) {
+        ShapeRenderer::set_camera(self, camera);
+    }
+
+    fn background_color(&mut self, background_color: Color) {
+        ShapeRenderer::background_color(self, background_color);
+    }
+
+    fn add_textures(&mut self, textures: &[&[u8]]) -> Vec<TextureId> {
+        let gpu = gpu();
+        self.add_textures_from_bytes(textures, &gpu.device, &gpu.queue)
+            .unwrap()
+    }
+
+    fn set_max_atlas_size(&mut self, max_atlas_size: u32) {
+        ShapeRenderer::set_max_atlas_size(self, max_atlas_size);
+    }
+}
+
+impl Scene for CpuRenderer {
+    fn rect(&mut self) -> &mut Rect {
+        CpuRenderer::rect(self)
+    }
+
+    fn oval(&mut self) -> &mut Oval {
+        CpuRenderer::oval(self)
+    }
+
+    fn image(&mut self, texture: TextureId) -> &mut Image {
+        CpuRenderer::image(self, texture).unwrap()
+    }
+
+    fn set_frame_offset(&mut self, frame_offset: (f32, f32)) {
+        CpuRenderer::set_frame_offset(self, frame_offset);
+    }
+
+    fn set_camera(&mut self, camera: Option<Camera2D>) {
+        CpuRenderer::set_camera(self, camera);
+    }
+
+    fn background_color(&mut self, background_color: Color) {
+        CpuRenderer::background_color(self, background_color);
+    }
+
+    fn add_textures(&mut self, textures: &[&[u8]]) -> Vec<TextureId> {
+        self.add_textures_from_bytes(textures).unwrap()
+    }
+
+    fn set_max_atlas_size(&mut self, max_atlas_size: u32) {
+        CpuRenderer::set_max_atlas_size(self, max_atlas_size);
+    }
+}
+
+struct Gpu {
+    device: Device,
+    queue: Queue,
+    /// the software adapters do not like being used by many threads at once
+    lock: Mutex<()>,
+}
+
+fn gpu() -> &'static Gpu {
+    static GPU: OnceLock<Gpu> = OnceLock::new();
+
+    GPU.get_or_init(|| {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+
+        // the software adapter gives the same result on every machine
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            force_fallback_adapter: true,
+            compatible_surface: None,
+        }))
+        .expect("golden tests need a fallback adapter like llvmpipe or lavapipe");
+
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("golden_test_device"),
+                features: wgpu::Features::empty(),
+                limits: adapter.limits(),
+            },
+            None,
+        ))
+        .expect("could not create device");
+
+        Gpu {
+            device,
+            queue,
+            lock: Mutex::new(()),
+        }
+    })
+}
+
+/// gives a [ShapeRenderer] for an image of [SIZE] with a frame of the same size
+pub fn with_renderer<T>(f: impl FnOnce(&mut ShapeRenderer, &Device, &Queue) -> T) -> T {
+    with_sample_count(1, f)
+}
+
+/// like [with_renderer] with more than one sample per pixel which get resolved into the image
+pub fn with_sample_count<T>(
+    sample_count: u32,
+    f: impl FnOnce(&mut ShapeRenderer, &Device, &Queue) -> T,
+) -> T {
+    let gpu = gpu();
+    let _guard = gpu
+        .lock
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut shape_renderer = ShapeRenderer::with_format(&gpu.device, FORMAT, SIZE, sample_count);
+    shape_renderer.set_frame_size((SIZE.0 as f32, SIZE.1 as f32));
+
+    f(&mut shape_renderer, &gpu.device, &gpu.queue)
+}
+
+/// renders the scene with a frame of the size of the image
+pub fn render(scene: impl FnOnce(&mut ShapeRenderer, &Device, &Queue)) -> RgbaImage {
+    render_with_samples(1, scene)
+}
+
+/// renders the scene with the sample count and a frame of the size of the image
+pub fn render_with_samples(
+    sample_count: u32,
+    scene: impl FnOnce(&mut ShapeRenderer, &Device, &Queue),
+) -> RgbaImage {
+    with_sample_count(sample_count, |shape_renderer, device, queue| {
+        scene(shape_renderer, device, queue);
+
+        let target = OffscreenTarget::new(device, SIZE, FORMAT);
+        shape_renderer.render_to_image(device, queue, &target)
+    })
+}
+
+/// renders the scene with the [CpuRenderer] and a frame of the size of the image
+pub fn render_cpu(scene: impl FnOnce(&mut CpuRenderer)) -> RgbaImage {
+    let mut cpu_renderer = CpuRenderer::new();
+    cpu_renderer.set_frame_size((SIZE.0 as f32, SIZE.1 as f32));
+
+    scene(&mut cpu_renderer);
+
+    cpu_renderer.render(SIZE)
+}
+
+/// renders the scene on the gpu and compares it with the reference image with the default [Tolerance]
+pub fn assert_scene(name: &str, scene: fn(&mut dyn Scene)) {
+    assert_scene_with(name, Tolerance::default(), scene)
+}
+
+pub fn assert_scene_with(name: &str, tolerance: Tolerance, scene: fn(&mut dyn Scene)) {
+    assert_image(
+        name,
+        tolerance,
+        &render(|shape_renderer, _, _| scene(shape_renderer)),
+    );
+}
+
+/// renders the scene with the [CpuRenderer] and compares it with the reference image of the gpu
+pub fn assert_cpu_scene(name: &str, tolerance: Tolerance, scene: fn(&mut dyn Scene)) {
+    compare(
+        name,
+        tolerance,
+        &render_cpu(|cpu_renderer| scene(cpu_renderer)),
+        "cpu",
+    );
+}
+
+/// compares the image with `tests/reference/<name>.png`
+///
+/// the reference only gets written if `UPDATE_REFERENCES` is set
+pub fn assert_image(name: &str, tolerance: Tolerance, image: &RgbaImage) {
+    let reference_path = reference_dir().join(format!("{}.png", name));
+
+    if std::env::var_os("UPDATE_REFERENCES").is_some() {
+        image.save(&reference_path).unwrap();
+        eprintln!("wrote reference {}", reference_path.display());
+        return;
+    }
+
+    compare(name, tolerance, image, "gpu");
+}
+
+fn compare(name: &str, tolerance: Tolerance, image: &RgbaImage, renderer: &str) {
+    let reference_path = reference_dir().join(format!("{}.png", name));
+    let reference = image::open(&reference_path)
+        .unwrap_or_else(|_| {
+            panic!(
+                "{}: missing reference {}, rerun with UPDATE_REFERENCES=1",
+                name,
+                reference_path.display()
+            )
+        })
+        .to_rgba8();
+
+    assert_eq!(
+        reference.dimensions(),
+        image.dimensions(),
+        "{}: the reference has a different size",
+        name
+    );
+
+    let (diff, differing) = diff(&reference, image, tolerance.channel);
+
+    if differing > tolerance.pixels {
+        let output_dir = output_dir();
+        let actual_path = output_dir.join(format!("{}.{}.png", name, renderer));
+        let diff_path = output_dir.join(format!("{}.{}.diff.png", name, renderer));
+
+        image.save(&actual_path).unwrap();
+        diff.save(&diff_path).unwrap();
+
+        panic!(
+            "{}: {} pixels of the {} renderer differ from the reference (allowed {}), see {} and {}",
+            name,
+            differing,
+            renderer,
+            tolerance.pixels,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+/// differing pixels are red, the others a faded version of the reference
+fn diff(reference: &RgbaImage, image: &RgbaImage, channel_tolerance: u8) -> (RgbaImage, usize) {
+    let mut differing = 0;
+
+    let diff = RgbaImage::from_fn(reference.width(), reference.height(), |x, y| {
+        let expected = reference.get_pixel(x, y);
+        let actual = image.get_pixel(x, y);
+
+        let matches = expected
+            .0
+            .iter()
+            .zip(actual.0.iter())
+            .all(|(a, b)| a.abs_diff(*b) <= channel_tolerance);
+
+        if matches {
+            let [r, g, b, _] = expected.0;
+            Rgba([r / 4 + 191, g / 4 + 191, b / 4 + 191, 255])
+        } else {
+            differing += 1;
+            Rgba([255, 0, 0, 255])
+        }
+    });
+
+    (diff, differing)
+}
+
+fn reference_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("reference")
+}
+
+fn output_dir() -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("golden");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
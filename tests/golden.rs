@@ -0,0 +1,813 @@
+use std::f32::consts::PI;
+
+use wgpu::Color;
+
+use wgpu_shapes::camera::Camera2D;
+use wgpu_shapes::shape::line::LineCap;
+use wgpu_shapes::shape::path::PathBuilder;
+use wgpu_shapes::shape::shapes::BasicShape;
+use wgpu_shapes::shape_renderer::RenderMode;
+
+mod common;
+
+use common::{
+    assert_cpu_scene, assert_image, assert_scene, render, render_with_samples, Scene, Tolerance,
+};
+
+/// pixels which lie right on an edge may differ between the gpu and the [CpuRenderer](wgpu_shapes::cpu_renderer::CpuRenderer)
+const CPU_TOLERANCE: Tolerance = Tolerance {
+    channel: 2,
+    pixels: 100,
+};
+
+fn rects(shape_renderer: &mut dyn Scene) {
+    shape_renderer.rect().pos(-50.0, 25.0).scale(60.0, 40.0);
+
+    shape_renderer
+        .rect()
+        .pos(40.0, 20.0)
+        .scale(80.0, 30.0)
+        .color(1.0, 0.0, 0.0);
+
+    shape_renderer
+        .rect()
+        .pos(0.0, -40.0)
+        .scale(120.0, 20.0)
+        .color(0.0, 0.4, 1.0)
+        .alpha(0.5);
+}
+
+fn ovals(shape_renderer: &mut dyn Scene) {
+    shape_renderer
+        .oval()
+        .pos(-50.0, 0.0)
+        .scale(80.0, 80.0)
+        .color(0.0, 0.6, 0.0);
+
+    shape_renderer
+        .oval()
+        .pos(50.0, 20.0)
+        .scale(80.0, 40.0)
+        .color(0.2, 0.2, 0.8)
+        .segment_count(6);
+
+    shape_renderer
+        .oval()
+        .pos(50.0, -40.0)
+        .scale(60.0, 30.0)
+        .color(1.0, 0.5, 0.0)
+        .stroke_width(4.0)
+        .stroke_color(0.0, 0.0, 0.0);
+}
+
+/// segment counts which used to round up to an extra segment and counts below three
+fn oval_segments(shape_renderer: &mut dyn Scene) {
+    shape_renderer
+        .oval()
+        .pos(-50.0, 20.0)
+        .scale(90.0, 90.0)
+        .color(0.0, 0.6, 0.0)
+        .segment_count(11);
+
+    shape_renderer
+        .oval()
+        .pos(45.0, 30.0)
+        .scale(80.0, 70.0)
+        .color(0.2, 0.2, 0.8)
+        .segment_count(22)
+        .stroke_width(3.0)
+        .stroke_color(0.0, 0.0, 0.0);
+
+    shape_renderer
+        .oval()
+        .pos(-60.0, -50.0)
+        .scale(40.0, 40.0)
+        .color(1.0, 0.5, 0.0)
+        .segment_count(2);
+
+    shape_renderer
+        .oval()
+        .pos(0.0, -40.0)
+        .scale(60.0, 50.0)
+        .color(1.0, 0.0, 0.0)
+        .alpha(0.5)
+        .segment_count(11);
+
+    shape_renderer
+        .oval()
+        .pos(60.0, -45.0)
+        .scale(50.0, 40.0)
+        .color(0.5, 0.0, 0.5)
+        .segment_count(51);
+}
+
+fn rotation(shape_renderer: &mut dyn Scene) {
+    shape_renderer
+        .rect()
+        .pos(-50.0, 0.0)
+        .scale(80.0, 40.0)
+        .rotation(PI / 6.0)
+        .color(1.0, 0.0, 0.0);
+
+    shape_renderer
+        .oval()
+        .pos(50.0, 0.0)
+        .scale(90.0, 30.0)
+        .rotation(-PI / 4.0)
+        .color(0.0, 0.0, 1.0);
+}
+
+fn layers(shape_renderer: &mut dyn Scene) {
+    shape_renderer
+        .rect()
+        .scale(120.0, 120.0)
+        .color(1.0, 0.0, 0.0)
+        .layer(0);
+
+    shape_renderer
+        .oval()
+        .scale(90.0, 90.0)
+        .color(0.0, 1.0, 0.0)
+        .layer(2);
+
+    // added later but below the oval
+    shape_renderer
+        .rect()
+        .scale(100.0, 20.0)
+        .color(0.0, 0.0, 1.0)
+        .layer(1);
+
+    shape_renderer
+        .rect()
+        .pos(30.0, 30.0)
+        .scale(60.0, 60.0)
+        .color(1.0, 1.0, 0.0)
+        .alpha(0.5)
+        .layer(3);
+}
+
+fn translucent(shape_renderer: &mut dyn Scene) {
+    shape_renderer
+        .rect()
+        .pos(-40.0, 0.0)
+        .scale(150.0, 20.0)
+        .color(0.0, 0.0, 0.0);
+
+    // different layers, the higher one is blended last although it got added first
+    shape_renderer
+        .oval()
+        .pos(-45.0, 15.0)
+        .scale(70.0, 70.0)
+        .color(0.0, 0.0, 1.0)
+        .alpha(0.5)
+        .layer(2);
+    shape_renderer
+        .rect()
+        .pos(-65.0, 15.0)
+        .scale(60.0, 60.0)
+        .color(1.0, 0.0, 0.0)
+        .alpha(0.5)
+        .layer(1);
+
+    // the same layer, blended in the order they got added
+    shape_renderer
+        .rect()
+        .pos(30.0, 25.0)
+        .scale(60.0, 60.0)
+        .color(0.0, 0.8, 0.0)
+        .alpha(0.6)
+        .layer(1);
+    shape_renderer
+        .oval()
+        .pos(60.0, 25.0)
+        .scale(60.0, 60.0)
+        .color(1.0, 0.5, 0.0)
+        .alpha(0.6)
+        .layer(1);
+
+    // the translucent stroke of the rect is blended after the fill of the oval which gets drawn later
+    shape_renderer
+        .rect()
+        .pos(10.0, -45.0)
+        .scale(80.0, 40.0)
+        .color(0.0, 0.5, 1.0)
+        .alpha(0.5)
+        .stroke_width(8.0)
+        .stroke_color_from_color(Color {
+            r: 1.0,
+            g: 0.0,
+            b: 1.0,
+            a: 0.5,
+        });
+    shape_renderer
+        .oval()
+        .pos(55.0, -45.0)
+        .scale(50.0, 40.0)
+        .color(1.0, 1.0, 0.0)
+        .alpha(0.5);
+}
+
+fn frame_offset(shape_renderer: &mut dyn Scene) {
+    shape_renderer.set_frame_offset((-60.0, 30.0));
+    shape_renderer.background_color(Color::BLACK);
+
+    shape_renderer.rect().scale(50.0, 50.0).color(1.0, 0.0, 0.0);
+
+    shape_renderer
+        .oval()
+        .pos(60.0, -30.0)
+        .scale(40.0, 40.0)
+        .color(0.0, 1.0, 1.0);
+}
+
+fn camera(shape_renderer: &mut dyn Scene) {
+    let mut camera = Camera2D::new((200.0, 150.0));
+    camera
+        .set_position((40.0, 20.0))
+        .set_rotation(PI / 6.0)
+        .zoom_at((150.0, 40.0), 1.5);
+
+    shape_renderer.set_camera(Some(camera));
+
+    shape_renderer.rect().pos(40.0, 20.0).scale(60.0, 40.0);
+
+    shape_renderer
+        .oval()
+        .pos(0.0, 0.0)
+        .scale(40.0, 40.0)
+        .color(1.0, 0.0, 0.0)
+        .layer(1);
+
+    shape_renderer
+        .rect()
+        .pos(80.0, 50.0)
+        .scale(30.0, 30.0)
+        .color(0.0, 0.8, 0.0)
+        .stroke_width(3.0)
+        .stroke_color(0.0, 0.0, 0.0);
+}
+
+fn hud(shape_renderer: &mut dyn Scene) {
+    let mut camera = Camera2D::new((200.0, 150.0));
+    camera
+        .set_position((30.0, -10.0))
+        .set_rotation(PI / 5.0)
+        .set_zoom(2.0);
+
+    shape_renderer.set_camera(Some(camera));
+    shape_renderer.set_frame_offset((25.0, 25.0));
+
+    shape_renderer
+        .rect()
+        .pos(30.0, -10.0)
+        .scale(50.0, 30.0)
+        .color(0.0, 0.8, 0.0)
+        .layer(1);
+
+    // bar along the top of the screen, behind the world rect
+    shape_renderer
+        .rect()
+        .screen_space(true)
+        .pos(0.0, 55.0)
+        .scale(180.0, 20.0)
+        .color(0.2, 0.2, 0.2);
+
+    shape_renderer
+        .oval()
+        .screen_space(true)
+        .pos(-80.0, -55.0)
+        .scale(20.0, 20.0)
+        .color(1.0, 0.0, 0.0)
+        .stroke_width(2.0)
+        .layer(2);
+
+    shape_renderer
+        .rect()
+        .screen_space(true)
+        .pos(0.0, 0.0)
+        .scale(40.0, 40.0)
+        .color(0.0, 0.0, 1.0)
+        .alpha(0.5)
+        .layer(2);
+}
+
+fn images(shape_renderer: &mut dyn Scene) {
+    let textures = shape_renderer.add_textures(&[
+        include_bytes!("../examples/img.png").as_slice(),
+        include_bytes!("../examples/img2.png").as_slice(),
+    ]);
+
+    shape_renderer
+        .image(textures[0])
+        .pos(-50.0, 0.0)
+        .scale(80.0, 80.0);
+
+    shape_renderer
+        .image(textures[1])
+        .pos(50.0, 0.0)
+        .scale(80.0, 60.0)
+        .rotation(PI / 8.0)
+        .layer(1);
+
+    shape_renderer
+        .image(textures[0])
+        .pos(0.0, -40.0)
+        .scale(40.0, 40.0)
+        .alpha(0.5)
+        .layer(2);
+}
+
+fn anchor(shape_renderer: &mut dyn Scene) {
+    let textures = shape_renderer.add_textures(&[include_bytes!("../examples/img.png").as_slice()]);
+
+    // hands of a clock which rotate around their bottom end
+    shape_renderer
+        .rect()
+        .pos(-50.0, 0.0)
+        .scale(6.0, 50.0)
+        .anchor(0.0, -1.0)
+        .rotation(-PI / 3.0)
+        .color(1.0, 0.0, 0.0);
+
+    shape_renderer
+        .rect()
+        .pos(-50.0, 0.0)
+        .scale(4.0, 35.0)
+        .anchor(0.0, -1.0)
+        .rotation(PI / 2.0)
+        .stroke_width(1.0);
+
+    shape_renderer
+        .oval()
+        .pos(40.0, 40.0)
+        .scale(60.0, 30.0)
+        .anchor(1.0, 1.0)
+        .rotation(PI / 6.0)
+        .color(0.0, 0.6, 0.0)
+        .layer(1);
+
+    shape_renderer
+        .image(textures[0])
+        .pos(10.0, -60.0)
+        .scale(50.0, 50.0)
+        .anchor(-1.0, -1.0)
+        .rotation(PI / 8.0);
+}
+
+fn transform(shape_renderer: &mut dyn Scene) {
+    let textures = shape_renderer.add_textures(&[include_bytes!("../examples/img.png").as_slice()]);
+
+    // skewed to the right
+    shape_renderer
+        .rect()
+        .pos(-50.0, 20.0)
+        .scale(50.0, 40.0)
+        .transform([[1.0, 0.5, 0.0], [0.0, 1.0, 0.0]])
+        .color(1.0, 0.0, 0.0)
+        .stroke_width(4.0);
+
+    shape_renderer
+        .oval()
+        .pos(20.0, 20.0)
+        .scale(60.0, 30.0)
+        .rotation(PI / 4.0)
+        .transform([[1.0, 0.0, 30.0], [0.0, 0.5, 0.0]])
+        .color(0.0, 0.6, 0.0);
+
+    // mirrored along the y axis
+    shape_renderer
+        .image(textures[0])
+        .pos(40.0, -40.0)
+        .scale(50.0, 50.0)
+        .transform([[-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+}
+
+fn atlas_pages(shape_renderer: &mut dyn Scene) {
+    // both images do not fit onto one page together
+    shape_renderer.set_max_atlas_size(1024);
+    let textures = shape_renderer.add_textures(&[
+        include_bytes!("../examples/img.png").as_slice(),
+        include_bytes!("../examples/img2.png").as_slice(),
+    ]);
+
+    shape_renderer
+        .image(textures[0])
+        .pos(-50.0, 0.0)
+        .scale(80.0, 80.0);
+    shape_renderer
+        .image(textures[1])
+        .pos(50.0, 0.0)
+        .scale(80.0, 80.0)
+        .alpha(0.5);
+}
+
+#[test]
+fn rects_gpu() {
+    assert_scene("rects", rects);
+}
+
+#[test]
+fn rects_cpu() {
+    assert_cpu_scene("rects", CPU_TOLERANCE, rects);
+}
+
+#[test]
+fn ovals_gpu() {
+    assert_scene("ovals", ovals);
+}
+
+#[test]
+fn ovals_cpu() {
+    assert_cpu_scene("ovals", CPU_TOLERANCE, ovals);
+}
+
+#[test]
+fn oval_segments_gpu() {
+    assert_scene("oval_segments", oval_segments);
+}
+
+#[test]
+fn oval_segments_cpu() {
+    assert_cpu_scene("oval_segments", CPU_TOLERANCE, oval_segments);
+}
+
+#[test]
+fn rotation_gpu() {
+    assert_scene("rotation", rotation);
+}
+
+#[test]
+fn rotation_cpu() {
+    assert_cpu_scene("rotation", CPU_TOLERANCE, rotation);
+}
+
+#[test]
+fn layers_gpu() {
+    assert_scene("layers", layers);
+}
+
+#[test]
+fn layers_cpu() {
+    assert_cpu_scene("layers", CPU_TOLERANCE, layers);
+}
+
+#[test]
+fn translucent_gpu() {
+    assert_scene("translucent", translucent);
+}
+
+#[test]
+fn translucent_cpu() {
+    assert_cpu_scene("translucent", CPU_TOLERANCE, translucent);
+}
+
+#[test]
+fn frame_offset_gpu() {
+    assert_scene("frame_offset", frame_offset);
+}
+
+#[test]
+fn frame_offset_cpu() {
+    assert_cpu_scene("frame_offset", CPU_TOLERANCE, frame_offset);
+}
+
+#[test]
+fn images_gpu() {
+    assert_scene("images", images);
+}
+
+#[test]
+fn images_cpu() {
+    assert_cpu_scene("images", CPU_TOLERANCE, images);
+}
+
+#[test]
+fn camera_gpu() {
+    assert_scene("camera", camera);
+}
+
+#[test]
+fn camera_cpu() {
+    assert_cpu_scene("camera", CPU_TOLERANCE, camera);
+}
+
+#[test]
+fn hud_gpu() {
+    assert_scene("hud", hud);
+}
+
+#[test]
+fn hud_cpu() {
+    assert_cpu_scene("hud", CPU_TOLERANCE, hud);
+}
+
+#[test]
+fn anchor_gpu() {
+    assert_scene("anchor", anchor);
+}
+
+#[test]
+fn anchor_cpu() {
+    assert_cpu_scene("anchor", CPU_TOLERANCE, anchor);
+}
+
+#[test]
+fn transform_gpu() {
+    assert_scene("transform", transform);
+}
+
+#[test]
+fn transform_cpu() {
+    assert_cpu_scene("transform", CPU_TOLERANCE, transform);
+}
+
+#[test]
+fn atlas_pages_gpu() {
+    assert_scene("atlas_pages", atlas_pages);
+}
+
+#[test]
+fn atlas_pages_cpu() {
+    assert_cpu_scene("atlas_pages", CPU_TOLERANCE, atlas_pages);
+}
+
+/// cpu generated meshes, only drawn by the gpu
+#[test]
+fn meshes() {
+    let image = render(|shape_renderer, device, queue| {
+        // overlapping opaque and translucent lines of the same layer
+        for i in 0..40 {
+            let x = -90.0 + i as f32 * 4.5;
+            let line = shape_renderer.polyline(&[(x, -60.0), (x + 20.0, 0.0), (x, 60.0)]);
+            line.thickness(3.0).layer((i % 3) as u16);
+
+            if i % 2 == 0 {
+                line.color(1.0, 0.0, 0.0).alpha(0.4);
+            } else if i % 5 == 0 {
+                line.color(0.0, 0.0, 0.0);
+            } else {
+                line.color(0.0, 0.5, 1.0).alpha(0.6);
+            }
+        }
+
+        // splits the translucent lines of its layer
+        shape_renderer
+            .rect()
+            .pos(0.0, 0.0)
+            .scale(60.0, 20.0)
+            .color(0.0, 0.8, 0.0)
+            .alpha(0.5)
+            .layer(1);
+
+        shape_renderer
+            .polygon(&[(-80.0, -70.0), (-20.0, -70.0), (-50.0, -20.0)])
+            .color(1.0, 0.6, 0.0)
+            .layer(1);
+        shape_renderer
+            .polygon(&[(-70.0, -60.0), (-30.0, -60.0), (-50.0, -30.0)])
+            .color(0.4, 0.0, 0.8)
+            .alpha(0.5)
+            .layer(1);
+
+        shape_renderer
+            .rounded_rect()
+            .pos(50.0, 40.0)
+            .scale(60.0, 40.0)
+            .corner_radius(10.0)
+            .color(0.2, 0.2, 0.2)
+            .stroke_width(6.0)
+            .stroke_color_from_color(Color {
+                r: 1.0,
+                g: 1.0,
+                b: 0.0,
+                a: 0.5,
+            });
+
+        let texture = shape_renderer
+            .add_texture_from_bytes(include_bytes!("../examples/img.png"), device, queue)
+            .unwrap();
+        shape_renderer
+            .rounded_image(texture)
+            .unwrap()
+            .pos(50.0, -40.0)
+            .scale(50.0, 50.0)
+            .corner_radius(12.0);
+        shape_renderer
+            .rounded_image(texture)
+            .unwrap()
+            .pos(70.0, -50.0)
+            .scale(40.0, 40.0)
+            .corner_radius(8.0)
+            .alpha(0.5)
+            .layer(1);
+    });
+
+    assert_image("meshes", Tolerance::default(), &image);
+}
+
+/// a transformed or anchored line or path looks like one whose points got moved beforehand
+#[test]
+fn transformed_meshes() {
+    // rotated by 90 degrees and moved, keeps the thickness of the lines
+    let turn = [[0.0, -1.0, 30.0], [1.0, 0.0, -10.0]];
+    let skew = [[1.0, 0.5, 0.0], [0.0, 1.0, 0.0]];
+    let apply = |m: [[f32; 3]; 2], points: &[(f32, f32)]| -> Vec<(f32, f32)> {
+        points
+            .iter()
+            .map(|(x, y)| {
+                (
+                    m[0][0] * x + m[0][1] * y + m[0][2],
+                    m[1][0] * x + m[1][1] * y + m[1][2],
+                )
+            })
+            .collect()
+    };
+
+    let polyline = [(-60.0, -20.0), (-20.0, 10.0), (10.0, -20.0)];
+    let straight = [(-50.0, 40.0), (0.0, 60.0)];
+    let outline = [(20.0, -60.0), (80.0, -60.0), (60.0, -20.0)];
+
+    let shift = |anchor: (f32, f32), points: &[(f32, f32)]| -> Vec<(f32, f32)> {
+        points
+            .iter()
+            .map(|(x, y)| (x - anchor.0, y - anchor.1))
+            .collect()
+    };
+
+    let path = |points: &[(f32, f32)]| {
+        points
+            .iter()
+            .skip(1)
+            .fold(
+                PathBuilder::new().move_to(points[0].0, points[0].1),
+                |path, p| path.line_to(p.0, p.1),
+            )
+            .close()
+            .build()
+    };
+
+    for render_mode in [RenderMode::Tessellated, RenderMode::Sdf] {
+        let transformed = render(|shape_renderer, _, _| {
+            shape_renderer.set_render_mode(render_mode);
+            shape_renderer
+                .polyline(&polyline)
+                .thickness(6.0)
+                .cap(LineCap::Round)
+                .transform(turn);
+            shape_renderer
+                .line(straight[0], straight[1])
+                .thickness(8.0)
+                .color(1.0, 0.0, 0.0)
+                .anchor(-50.0, 40.0)
+                .transform(turn);
+            shape_renderer
+                .fill_path(&path(&outline))
+                .color(0.0, 0.6, 0.0)
+                .transform(skew);
+            shape_renderer
+                .stroke_path(&path(&outline))
+                .thickness(4.0)
+                .color(0.0, 0.0, 0.0)
+                .anchor(20.0, -60.0)
+                .transform(turn);
+        });
+
+        let moved = render(|shape_renderer, _, _| {
+            shape_renderer.set_render_mode(render_mode);
+            shape_renderer
+                .polyline(&apply(turn, &polyline))
+                .thickness(6.0)
+                .cap(LineCap::Round);
+            let straight = apply(turn, &shift((-50.0, 40.0), &straight));
+            shape_renderer
+                .line(straight[0], straight[1])
+                .thickness(8.0)
+                .color(1.0, 0.0, 0.0);
+            shape_renderer
+                .fill_path(&path(&apply(skew, &outline)))
+                .color(0.0, 0.6, 0.0);
+            shape_renderer
+                .stroke_path(&path(&apply(turn, &shift((20.0, -60.0), &outline))))
+                .thickness(4.0)
+                .color(0.0, 0.0, 0.0);
+        });
+
+        let differing = transformed
+            .pixels()
+            .zip(moved.pixels())
+            .filter(|(a, b)| a.0.iter().zip(b.0.iter()).any(|(a, b)| a.abs_diff(*b) > 2))
+            .count();
+        assert!(
+            differing <= Tolerance::default().pixels,
+            "{:?}: {} pixels differ",
+            render_mode,
+            differing
+        );
+    }
+}
+
+/// every shape which the distance shader draws, only drawn by the gpu
+#[test]
+fn sdf() {
+    let image = render(|shape_renderer, _, _| {
+        shape_renderer.set_render_mode(RenderMode::Sdf);
+
+        shape_renderer
+            .rect()
+            .pos(-60.0, 35.0)
+            .scale(50.0, 40.0)
+            .rotation(PI / 8.0)
+            .color(1.0, 0.0, 0.0)
+            .stroke_width(4.0);
+
+        shape_renderer
+            .oval()
+            .pos(0.0, 35.0)
+            .scale(60.0, 35.0)
+            .color(0.0, 0.6, 0.0)
+            .stroke_width(3.0)
+            .stroke_color(1.0, 1.0, 0.0);
+
+        // only the stroke on top of the oval
+        shape_renderer
+            .oval()
+            .pos(20.0, 20.0)
+            .scale(30.0, 30.0)
+            .fill(false)
+            .stroke_width(2.0)
+            .layer(1);
+
+        shape_renderer
+            .rounded_rect()
+            .pos(60.0, 35.0)
+            .scale(50.0, 50.0)
+            .corner_radii(20.0, 0.0, 10.0, 5.0)
+            .color(0.4, 0.0, 0.8)
+            .stroke_width(5.0)
+            .stroke_color_from_color(Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.5,
+            });
+
+        // translucent shapes of different layers get sorted
+        shape_renderer
+            .rounded_rect()
+            .pos(-40.0, -35.0)
+            .scale(70.0, 40.0)
+            .corner_radius(12.0)
+            .color(0.0, 0.5, 1.0)
+            .alpha(0.5)
+            .layer(2);
+        shape_renderer
+            .oval()
+            .pos(-20.0, -35.0)
+            .scale(40.0, 40.0)
+            .color(1.0, 0.5, 0.0)
+            .alpha(0.7)
+            .layer(1);
+
+        shape_renderer
+            .line((10.0, -60.0), (80.0, -10.0))
+            .thickness(8.0)
+            .cap(LineCap::Round)
+            .color(0.2, 0.2, 0.2);
+        shape_renderer
+            .line((10.0, -10.0), (80.0, -60.0))
+            .thickness(6.0)
+            .cap(LineCap::Square)
+            .color(1.0, 0.0, 0.5)
+            .alpha(0.6);
+    });
+
+    assert_image("sdf", Tolerance::default(), &image);
+}
+
+/// the multisampled targets get resolved into the image, only drawn by the gpu
+#[test]
+fn multisampled() {
+    let image = render_with_samples(4, |shape_renderer, _, _| {
+        rotation(shape_renderer);
+
+        shape_renderer
+            .oval()
+            .pos(-60.0, -40.0)
+            .scale(50.0, 30.0)
+            .color(0.0, 0.6, 0.0)
+            .stroke_width(3.0);
+
+        // translucent shapes and meshes get resolved as well
+        shape_renderer
+            .polyline(&[(30.0, -60.0), (50.0, -20.0), (70.0, -60.0)])
+            .thickness(4.0)
+            .color(1.0, 0.0, 0.5)
+            .alpha(0.5)
+            .layer(1);
+        shape_renderer
+            .polygon(&[(-20.0, -60.0), (20.0, -60.0), (0.0, -25.0)])
+            .color(0.0, 0.5, 1.0);
+    });
+
+    assert_image("multisampled", Tolerance::default(), &image);
+}
@@ -0,0 +1,405 @@
+use std::f32::consts::PI;
+use std::io::Cursor;
+
+use image::{ImageOutputFormat, Rgba, RgbaImage};
+use wgpu_shapes::camera::Camera2D;
+use wgpu_shapes::hit_test::ShapeId;
+use wgpu_shapes::shape::path::PathBuilder;
+use wgpu_shapes::shape::shapes::BasicShape;
+
+use common::with_renderer;
+
+mod common;
+
+#[test]
+fn rotated_rect() {
+    with_renderer(|shape_renderer, _, _| {
+        shape_renderer
+            .rect()
+            .pos(10.0, 0.0)
+            .scale(100.0, 20.0)
+            .rotation(PI / 2.0);
+
+        assert_eq!(
+            shape_renderer.shape_at((10.0, 45.0)),
+            Some(ShapeId::Rect(0))
+        );
+        assert_eq!(
+            shape_renderer.shape_at((10.0, -45.0)),
+            Some(ShapeId::Rect(0))
+        );
+        assert_eq!(shape_renderer.shape_at((45.0, 0.0)), None);
+        assert_eq!(shape_renderer.shape_at((10.0, 55.0)), None);
+    });
+}
+
+#[test]
+fn anchor() {
+    with_renderer(|shape_renderer, _, _| {
+        // rotates around its bottom left corner which stays at (10,0)
+        shape_renderer
+            .rect()
+            .pos(10.0, 0.0)
+            .scale(40.0, 20.0)
+            .anchor(-1.0, -1.0)
+            .rotation(PI / 2.0);
+
+        assert_eq!(shape_renderer.shape_at((0.0, 35.0)), Some(ShapeId::Rect(0)));
+        assert_eq!(shape_renderer.shape_at((20.0, 10.0)), None);
+        assert_eq!(
+            shape_renderer.shapes_in((-15.0, 30.0), (-5.0, 45.0)),
+            vec![ShapeId::Rect(0)]
+        );
+    });
+}
+
+#[test]
+fn skew() {
+    with_renderer(|shape_renderer, _, _| {
+        shape_renderer
+            .rect()
+            .scale(20.0, 20.0)
+            .transform([[1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]);
+
+        // the top edge goes from (0,10) to (20,10)
+        assert_eq!(shape_renderer.shape_at((18.0, 9.0)), Some(ShapeId::Rect(0)));
+        assert_eq!(shape_renderer.shape_at((-18.0, 9.0)), None);
+        assert_eq!(shape_renderer.shapes_in((-20.0, 5.0), (-15.0, 8.0)), vec![]);
+
+        // collapsed onto a line
+        shape_renderer
+            .oval()
+            .scale(20.0, 20.0)
+            .transform([[1.0, 0.0, 0.0], [0.0, 0.0, 0.0]])
+            .layer(1);
+        assert_eq!(shape_renderer.shape_at((0.0, 0.0)), Some(ShapeId::Rect(0)));
+    });
+}
+
+#[test]
+fn oval_corners() {
+    with_renderer(|shape_renderer, _, _| {
+        shape_renderer.oval().scale(100.0, 50.0);
+
+        assert_eq!(shape_renderer.shape_at((49.0, 0.0)), Some(ShapeId::Oval(0)));
+        assert_eq!(shape_renderer.shape_at((0.0, 24.0)), Some(ShapeId::Oval(0)));
+        // inside of the bounding box but outside of the ellipse
+        assert_eq!(shape_renderer.shape_at((45.0, 20.0)), None);
+    });
+}
+
+#[test]
+fn layers_and_draw_order() {
+    with_renderer(|shape_renderer, _, _| {
+        shape_renderer.rect().scale(40.0, 40.0).layer(1);
+        shape_renderer.oval().scale(40.0, 40.0).layer(2);
+        shape_renderer
+            .rect()
+            .pos(30.0, 0.0)
+            .scale(40.0, 40.0)
+            .layer(2);
+
+        assert_eq!(shape_renderer.shape_at((0.0, 0.0)), Some(ShapeId::Oval(0)));
+        assert_eq!(
+            shape_renderer.shape_at((-18.0, 18.0)),
+            Some(ShapeId::Rect(0))
+        );
+
+        // same layer -> the earlier opaque shape stays in front
+        assert_eq!(shape_renderer.shape_at((15.0, 0.0)), Some(ShapeId::Rect(1)));
+
+        // translucent shapes get drawn over the opaque ones
+        shape_renderer
+            .oval()
+            .pos(20.0, 0.0)
+            .scale(20.0, 20.0)
+            .alpha(0.5)
+            .layer(2);
+        assert_eq!(shape_renderer.shape_at((15.0, 0.0)), Some(ShapeId::Oval(1)));
+    });
+}
+
+#[test]
+fn stroke_without_fill() {
+    with_renderer(|shape_renderer, _, _| {
+        shape_renderer
+            .rect()
+            .scale(40.0, 40.0)
+            .fill(false)
+            .stroke_width(4.0);
+
+        assert_eq!(shape_renderer.shape_at((0.0, 0.0)), None);
+        assert_eq!(shape_renderer.shape_at((18.0, 0.0)), Some(ShapeId::Rect(0)));
+    });
+}
+
+#[test]
+fn camera_and_screen_space() {
+    with_renderer(|shape_renderer, _, _| {
+        let mut camera = Camera2D::new((200.0, 150.0));
+        camera.set_position((100.0, 0.0)).set_zoom(2.0);
+        shape_renderer.set_camera(Some(camera));
+
+        shape_renderer.rect().pos(100.0, 0.0).scale(10.0, 10.0);
+
+        // 20x20 pixels in the top left of the screen
+        shape_renderer
+            .oval()
+            .screen_space(true)
+            .pos(-90.0, 65.0)
+            .scale(20.0, 20.0);
+
+        assert_eq!(
+            shape_renderer.shape_at((104.0, 0.0)),
+            Some(ShapeId::Rect(0))
+        );
+
+        let top_left = camera.screen_to_world((10.0, 10.0));
+        assert_eq!(shape_renderer.shape_at(top_left), Some(ShapeId::Oval(0)));
+        assert_eq!(shape_renderer.shape_at((-90.0, 65.0)), None);
+    });
+}
+
+#[test]
+fn image_alpha() {
+    // left half transparent
+    let texture = RgbaImage::from_fn(4, 4, |x, _| Rgba([255, 0, 0, if x < 2 { 0 } else { 255 }]));
+    let mut bytes = Cursor::new(vec![]);
+    texture
+        .write_to(&mut bytes, ImageOutputFormat::Png)
+        .unwrap();
+
+    with_renderer(|shape_renderer, device, queue| {
+        let texture = shape_renderer
+            .add_texture_from_bytes(bytes.get_ref(), device, queue)
+            .unwrap();
+        shape_renderer.image(texture).unwrap().scale(40.0, 40.0);
+
+        assert_eq!(
+            shape_renderer.shape_at((-10.0, 0.0)),
+            Some(ShapeId::Image(0))
+        );
+
+        shape_renderer.set_image_hit_alpha(Some(0.5));
+        assert_eq!(shape_renderer.shape_at((-10.0, 0.0)), None);
+        assert_eq!(
+            shape_renderer.shape_at((10.0, 0.0)),
+            Some(ShapeId::Image(0))
+        );
+    });
+}
+
+#[test]
+fn image_alpha_without_copies() {
+    let texture = RgbaImage::from_fn(4, 4, |x, _| Rgba([255, 0, 0, if x < 2 { 0 } else { 255 }]));
+    let mut bytes = Cursor::new(vec![]);
+    texture
+        .write_to(&mut bytes, ImageOutputFormat::Png)
+        .unwrap();
+
+    with_renderer(|shape_renderer, device, queue| {
+        shape_renderer.set_image_hit_alpha(Some(0.5));
+        shape_renderer.set_keep_texture_copies(false);
+
+        let dropped = shape_renderer
+            .add_texture_from_bytes(bytes.get_ref(), device, queue)
+            .unwrap();
+        shape_renderer.image(dropped).unwrap().scale(40.0, 40.0);
+
+        // only the bounds are left
+        assert_eq!(
+            shape_renderer.shape_at((-10.0, 0.0)),
+            Some(ShapeId::Image(0))
+        );
+
+        // the pixels do not come back
+        shape_renderer.set_keep_texture_copies(true);
+        assert_eq!(
+            shape_renderer.shape_at((-10.0, 0.0)),
+            Some(ShapeId::Image(0))
+        );
+
+        let kept = shape_renderer
+            .add_texture_from_bytes(bytes.get_ref(), device, queue)
+            .unwrap();
+        shape_renderer
+            .image(kept)
+            .unwrap()
+            .pos(60.0, 0.0)
+            .scale(40.0, 40.0);
+        assert_eq!(shape_renderer.shape_at((50.0, 0.0)), None);
+        assert_eq!(
+            shape_renderer.shape_at((70.0, 0.0)),
+            Some(ShapeId::Image(1))
+        );
+    });
+}
+
+#[test]
+fn area() {
+    with_renderer(|shape_renderer, _, _| {
+        shape_renderer.rect().pos(-50.0, 0.0).scale(20.0, 20.0);
+        shape_renderer
+            .rect()
+            .pos(0.0, 0.0)
+            .scale(60.0, 10.0)
+            .rotation(PI / 4.0)
+            .layer(1);
+        shape_renderer.oval().pos(50.0, 0.0).scale(40.0, 40.0);
+
+        assert_eq!(
+            shape_renderer.shapes_in((-100.0, -100.0), (100.0, 100.0)),
+            vec![ShapeId::Rect(1), ShapeId::Rect(0), ShapeId::Oval(0)]
+        );
+
+        // touches the corner of the bounding box of the oval but not the oval
+        assert_eq!(shape_renderer.shapes_in((65.0, 15.0), (80.0, 30.0)), vec![]);
+
+        // only reaches the rotated rect along its diagonal
+        assert_eq!(
+            shape_renderer.shapes_in((15.0, 15.0), (18.0, 18.0)),
+            vec![ShapeId::Rect(1)]
+        );
+        assert_eq!(
+            shape_renderer.shapes_in((15.0, -18.0), (18.0, -15.0)),
+            vec![]
+        );
+    });
+}
+
+#[test]
+fn meshes() {
+    with_renderer(|shape_renderer, _, _| {
+        // hidden by the triangle above it except for its corners
+        shape_renderer.rect().scale(40.0, 40.0);
+        shape_renderer
+            .polygon(&[(-30.0, -30.0), (30.0, -30.0), (0.0, 30.0)])
+            .layer(1);
+
+        assert_eq!(
+            shape_renderer.shape_at((0.0, 0.0)),
+            Some(ShapeId::Polygon(0))
+        );
+        assert_eq!(
+            shape_renderer.shape_at((-18.0, 18.0)),
+            Some(ShapeId::Rect(0))
+        );
+
+        shape_renderer
+            .line((-80.0, -60.0), (-40.0, -20.0))
+            .thickness(4.0);
+
+        assert_eq!(
+            shape_renderer.shape_at((-60.0, -40.0)),
+            Some(ShapeId::Line(0))
+        );
+        assert_eq!(shape_renderer.shape_at((-60.0, -36.0)), None);
+
+        let path = PathBuilder::new()
+            .move_to(40.0, -60.0)
+            .line_to(80.0, -60.0)
+            .line_to(80.0, -20.0)
+            .close()
+            .build();
+        shape_renderer.fill_path(&path);
+
+        assert_eq!(
+            shape_renderer.shape_at((75.0, -50.0)),
+            Some(ShapeId::Path(0))
+        );
+        assert_eq!(shape_renderer.shape_at((45.0, -25.0)), None);
+
+        // the rounded corner leaves the corner of the bounds free
+        shape_renderer
+            .rounded_rect()
+            .pos(60.0, 40.0)
+            .scale(40.0, 40.0)
+            .corner_radius(15.0);
+
+        assert_eq!(
+            shape_renderer.shape_at((60.0, 40.0)),
+            Some(ShapeId::RoundedRect(0))
+        );
+        assert_eq!(shape_renderer.shape_at((79.0, 59.0)), None);
+
+        shape_renderer
+            .ring()
+            .pos(-60.0, 40.0)
+            .scale(40.0, 40.0)
+            .inner_radius(0.5);
+
+        assert_eq!(shape_renderer.shape_at((-60.0, 40.0)), None);
+        assert_eq!(
+            shape_renderer.shape_at((-60.0, 55.0)),
+            Some(ShapeId::Ring(0))
+        );
+    });
+}
+
+#[test]
+fn mesh_stroke() {
+    with_renderer(|shape_renderer, _, _| {
+        shape_renderer
+            .polygon(&[(-20.0, -20.0), (20.0, -20.0), (20.0, 20.0), (-20.0, 20.0)])
+            .fill(false)
+            .stroke_width(4.0);
+
+        // a quarter with a translucent stroke above the opaque fill of the same layer
+        shape_renderer
+            .pie()
+            .pos(60.0, 0.0)
+            .scale(40.0, 40.0)
+            .stroke_width(4.0)
+            .stroke_color_from_color(wgpu::Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.5,
+            });
+        shape_renderer.rect().pos(60.0, 0.0).scale(40.0, 40.0);
+
+        assert_eq!(shape_renderer.shape_at((0.0, 0.0)), None);
+        assert_eq!(
+            shape_renderer.shape_at((18.5, 0.0)),
+            Some(ShapeId::Polygon(0))
+        );
+        assert_eq!(shape_renderer.shape_at((70.0, 1.5)), Some(ShapeId::Pie(0)));
+        assert_eq!(shape_renderer.shape_at((68.0, 8.0)), Some(ShapeId::Rect(0)));
+    });
+}
+
+#[test]
+fn mesh_area() {
+    with_renderer(|shape_renderer, _, _| {
+        shape_renderer
+            .polyline(&[(-60.0, 0.0), (0.0, 40.0), (60.0, 0.0)])
+            .thickness(2.0);
+        shape_renderer
+            .arc()
+            .pos(0.0, -30.0)
+            .scale(40.0, 40.0)
+            .angles(0.0, PI)
+            .layer(1);
+
+        assert_eq!(
+            shape_renderer.shapes_in((-100.0, -100.0), (100.0, 100.0)),
+            vec![ShapeId::Arc(0), ShapeId::Line(0)]
+        );
+
+        // below the bend of the line and above its ends
+        assert_eq!(
+            shape_renderer.shapes_in((-10.0, 10.0), (10.0, 30.0)),
+            vec![]
+        );
+
+        // the arc only covers the upper half of the oval
+        assert_eq!(
+            shape_renderer.shapes_in((-5.0, -45.0), (5.0, -35.0)),
+            vec![]
+        );
+        assert_eq!(
+            shape_renderer.shapes_in((-5.0, -15.0), (5.0, -5.0)),
+            vec![ShapeId::Arc(0)]
+        );
+    });
+}
@@ -0,0 +1,458 @@
+use std::f32::consts::PI;
+
+use image::RgbaImage;
+use wgpu::{Device, Queue};
+use wgpu_shapes::hit_test::ShapeId;
+use wgpu_shapes::render::offscreen::OffscreenTarget;
+use wgpu_shapes::shape::shapes::BasicShape;
+use wgpu_shapes::shape_renderer::{RenderMode, ShapeRenderer};
+
+use common::{render, with_renderer, FORMAT, SIZE};
+
+mod common;
+
+fn frame(shape_renderer: &mut ShapeRenderer, device: &Device, queue: &Queue) -> RgbaImage {
+    let target = OffscreenTarget::new(device, SIZE, FORMAT);
+    shape_renderer.render_to_image(device, queue, &target)
+}
+
+#[test]
+fn same_as_immediate() {
+    let expected = render(|shape_renderer, _, _| {
+        shape_renderer.rect().pos(-40.0, 0.0).scale(60.0, 40.0);
+        shape_renderer
+            .oval()
+            .pos(40.0, 0.0)
+            .scale(50.0, 50.0)
+            .color(1.0, 0.0, 0.0)
+            .stroke_width(4.0)
+            .layer(1);
+        shape_renderer
+            .rect()
+            .scale(40.0, 40.0)
+            .color(0.0, 1.0, 0.0)
+            .alpha(0.5)
+            .layer(2);
+    });
+
+    let retained = render(|shape_renderer, _, _| {
+        shape_renderer
+            .add_rect()
+            .1
+            .pos(-40.0, 0.0)
+            .scale(60.0, 40.0);
+        shape_renderer
+            .add_oval()
+            .1
+            .pos(40.0, 0.0)
+            .scale(50.0, 50.0)
+            .color(1.0, 0.0, 0.0)
+            .stroke_width(4.0)
+            .layer(1);
+        shape_renderer
+            .add_rect()
+            .1
+            .scale(40.0, 40.0)
+            .color(0.0, 1.0, 0.0)
+            .alpha(0.5)
+            .layer(2);
+    });
+
+    assert_eq!(expected, retained);
+}
+
+#[test]
+fn update_hide_and_remove() {
+    let expected = render(|shape_renderer, _, _| {
+        shape_renderer
+            .rect()
+            .pos(50.0, 20.0)
+            .scale(30.0, 30.0)
+            .rotation(PI / 4.0)
+            .color(1.0, 0.0, 0.0);
+        shape_renderer.oval().pos(-50.0, 0.0).scale(40.0, 20.0);
+    });
+
+    with_renderer(|shape_renderer, device, queue| {
+        let (first, _) = shape_renderer.add_rect();
+        let (second, _) = shape_renderer.add_oval();
+        let (third, _) = shape_renderer.add_rect();
+        let (hidden, _) = shape_renderer.add_oval();
+
+        frame(shape_renderer, device, queue);
+
+        // moves the last instance of the buffer into the gap
+        assert!(shape_renderer.remove(first));
+        assert!(!shape_renderer.remove(first));
+        assert!(!shape_renderer.contains(first));
+        assert!(shape_renderer.rect_mut(first).is_none());
+
+        shape_renderer
+            .rect_mut(third)
+            .unwrap()
+            .pos(50.0, 20.0)
+            .scale(30.0, 30.0)
+            .rotation(PI / 4.0)
+            .color(1.0, 0.0, 0.0);
+        shape_renderer
+            .oval_mut(second)
+            .unwrap()
+            .pos(-50.0, 0.0)
+            .scale(40.0, 20.0);
+        shape_renderer.set_visible(hidden, false);
+
+        assert!(shape_renderer.rect_mut(second).is_none());
+        assert!(!shape_renderer.is_visible(hidden));
+
+        // immediate shapes get cleared, retained ones stay
+        shape_renderer.rect().scale(100.0, 100.0);
+        frame(shape_renderer, device, queue);
+        shape_renderer.clear();
+
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+    });
+}
+
+#[test]
+fn removed_handles_stay_invalid() {
+    with_renderer(|shape_renderer, _, _| {
+        let (removed, _) = shape_renderer.add_rect();
+        shape_renderer.remove(removed);
+
+        let (added, _) = shape_renderer.add_rect();
+        assert_ne!(removed, added);
+        assert!(!shape_renderer.contains(removed));
+        assert!(shape_renderer.contains(added));
+
+        shape_renderer.clear_retained();
+        assert!(!shape_renderer.contains(added));
+    });
+}
+
+#[test]
+fn opaque_and_translucent() {
+    let expected = render(|shape_renderer, _, _| {
+        shape_renderer.rect().scale(60.0, 60.0).alpha(0.5).layer(1);
+        shape_renderer.oval().scale(80.0, 40.0).color(1.0, 0.0, 0.0);
+    });
+
+    with_renderer(|shape_renderer, device, queue| {
+        let (rect, _) = shape_renderer.add_rect();
+        shape_renderer
+            .rect_mut(rect)
+            .unwrap()
+            .scale(60.0, 60.0)
+            .layer(1);
+        let (oval, _) = shape_renderer.add_oval();
+        shape_renderer
+            .oval_mut(oval)
+            .unwrap()
+            .scale(80.0, 40.0)
+            .color(1.0, 0.0, 0.0)
+            .alpha(0.5);
+
+        frame(shape_renderer, device, queue);
+
+        // switch places between the opaque buffers and the translucent pass
+        shape_renderer.rect_mut(rect).unwrap().alpha(0.5);
+        shape_renderer.oval_mut(oval).unwrap().alpha(1.0);
+
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+    });
+}
+
+#[test]
+fn translucent_hide_and_remove() {
+    let expected = render(|shape_renderer, _, _| {
+        shape_renderer
+            .oval()
+            .pos(40.0, 20.0)
+            .scale(40.0, 40.0)
+            .color(0.0, 0.0, 1.0)
+            .alpha(0.5)
+            .segment_count(7)
+            .layer(1);
+    });
+
+    with_renderer(|shape_renderer, device, queue| {
+        let (hidden, _) = shape_renderer.add_rect();
+        shape_renderer
+            .rect_mut(hidden)
+            .unwrap()
+            .scale(60.0, 60.0)
+            .alpha(0.5);
+        let (removed, _) = shape_renderer.add_oval();
+        shape_renderer
+            .oval_mut(removed)
+            .unwrap()
+            .scale(80.0, 40.0)
+            .stroke_width(4.0)
+            .stroke_color_from_color(wgpu::Color {
+                r: 1.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.5,
+            });
+
+        // only drawn translucent, its segment count still needs a mesh
+        let (group, _) = shape_renderer.add_group(None);
+        let (moved, _) = shape_renderer.add_oval();
+        shape_renderer
+            .oval_mut(moved)
+            .unwrap()
+            .scale(40.0, 40.0)
+            .color(0.0, 0.0, 1.0)
+            .alpha(0.5)
+            .segment_count(7);
+        shape_renderer.set_parent(moved, Some(group));
+
+        frame(shape_renderer, device, queue);
+
+        shape_renderer.set_visible(hidden, false);
+        shape_renderer.remove(removed);
+        shape_renderer
+            .group_mut(group)
+            .unwrap()
+            .pos(40.0, 20.0)
+            .layer(1);
+
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+    });
+}
+
+#[test]
+fn sdf_mode() {
+    let expected = render(|shape_renderer, _, _| {
+        shape_renderer.set_render_mode(RenderMode::Sdf);
+        shape_renderer.rect().scale(60.0, 40.0).stroke_width(3.0);
+    });
+
+    with_renderer(|shape_renderer, device, queue| {
+        shape_renderer
+            .add_rect()
+            .1
+            .scale(60.0, 40.0)
+            .stroke_width(3.0);
+        frame(shape_renderer, device, queue);
+
+        shape_renderer.set_render_mode(RenderMode::Sdf);
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+    });
+}
+
+#[test]
+fn hit_test() {
+    with_renderer(|shape_renderer, _, _| {
+        shape_renderer.rect().scale(40.0, 40.0);
+        let (handle, oval) = shape_renderer.add_oval();
+        oval.scale(20.0, 20.0).layer(1);
+
+        assert_eq!(
+            shape_renderer.shape_at((0.0, 0.0)),
+            Some(ShapeId::Retained(handle))
+        );
+
+        shape_renderer.set_visible(handle, false);
+        assert_eq!(shape_renderer.shape_at((0.0, 0.0)), Some(ShapeId::Rect(0)));
+    });
+}
+
+#[test]
+fn group_transform() {
+    let expected = render(|shape_renderer, _, _| {
+        shape_renderer
+            .rect()
+            .pos(30.0, 10.0)
+            .scale(40.0, 20.0)
+            .color(1.0, 0.0, 0.0)
+            .stroke_width(4.0)
+            .layer(3);
+        shape_renderer.oval().pos(70.0, 10.0).scale(20.0, 20.0);
+    });
+
+    with_renderer(|shape_renderer, device, queue| {
+        let (group, _) = shape_renderer.add_group(None);
+
+        let (rect, _) = shape_renderer.add_rect();
+        shape_renderer
+            .rect_mut(rect)
+            .unwrap()
+            .scale(20.0, 10.0)
+            .color(1.0, 0.0, 0.0)
+            .stroke_width(2.0)
+            .layer(1);
+        let (oval, _) = shape_renderer.add_oval();
+        shape_renderer
+            .oval_mut(oval)
+            .unwrap()
+            .pos(20.0, 0.0)
+            .scale(10.0, 10.0);
+
+        assert!(shape_renderer.set_parent(rect, Some(group)));
+        assert!(shape_renderer.set_parent(oval, Some(group)));
+
+        frame(shape_renderer, device, queue);
+
+        // moving the group moves every shape inside
+        shape_renderer
+            .group_mut(group)
+            .unwrap()
+            .pos(30.0, 10.0)
+            .scale(2.0, 2.0)
+            .layer(2);
+
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+    });
+}
+
+#[test]
+fn group_skew() {
+    let expected = render(|shape_renderer, _, _| {
+        shape_renderer
+            .rect()
+            .scale(40.0, 20.0)
+            .rotation(PI / 4.0)
+            .transform([[2.0, 0.0, 0.0], [0.0, 1.0, 0.0]])
+            .stroke_width(3.0);
+    });
+
+    let retained = render(|shape_renderer, _, _| {
+        let (group, group_data) = shape_renderer.add_group(None);
+        group_data.scale(2.0, 1.0);
+
+        let (rect, _) = shape_renderer.add_rect();
+        shape_renderer
+            .rect_mut(rect)
+            .unwrap()
+            .scale(40.0, 20.0)
+            .rotation(PI / 4.0)
+            .stroke_width(3.0);
+        shape_renderer.set_parent(rect, Some(group));
+    });
+
+    assert_eq!(expected, retained);
+}
+
+#[test]
+fn nested_groups() {
+    with_renderer(|shape_renderer, _, _| {
+        shape_renderer
+            .rect()
+            .pos(0.0, 30.0)
+            .scale(30.0, 30.0)
+            .layer(4);
+
+        let (car, _) = shape_renderer.add_group(None);
+        let (wheel, group) = shape_renderer.add_group(Some(car));
+        group.pos(20.0, 0.0).layer(1);
+
+        let (tire, _) = shape_renderer.add_oval();
+        shape_renderer
+            .oval_mut(tire)
+            .unwrap()
+            .pos(10.0, 0.0)
+            .scale(10.0, 10.0)
+            .layer(2);
+        shape_renderer.set_parent(tire, Some(wheel));
+
+        // (10,0) in the wheel -> (30,0) in the car
+        assert_eq!(
+            shape_renderer.shape_at((30.0, 0.0)),
+            Some(ShapeId::Retained(tire))
+        );
+
+        shape_renderer
+            .group_mut(car)
+            .unwrap()
+            .rotation(PI / 2.0)
+            .layer(2);
+
+        // rotated around the origin of the car -> (0,30), layer 2 + 1 + 2 is above the rect
+        assert_eq!(shape_renderer.shape_at((30.0, 0.0)), None);
+        assert_eq!(
+            shape_renderer.shape_at((0.0, 34.0)),
+            Some(ShapeId::Retained(tire))
+        );
+
+        shape_renderer.group_mut(car).unwrap().layer(0);
+        assert_eq!(shape_renderer.shape_at((0.0, 34.0)), Some(ShapeId::Rect(0)));
+
+        // the shape is relative to the world again
+        shape_renderer.set_parent(tire, None);
+        assert_eq!(
+            shape_renderer.shape_at((10.0, 0.0)),
+            Some(ShapeId::Retained(tire))
+        );
+    });
+}
+
+#[test]
+fn parent_group_after_frame() {
+    let expected = render(|shape_renderer, _, _| {
+        shape_renderer
+            .oval()
+            .pos(-20.0, 40.0)
+            .scale(20.0, 20.0)
+            .color(0.0, 0.0, 1.0)
+            .layer(3);
+    });
+
+    with_renderer(|shape_renderer, device, queue| {
+        let (car, _) = shape_renderer.add_group(None);
+        let (wheel, group) = shape_renderer.add_group(Some(car));
+        group.pos(10.0, 0.0).layer(1);
+
+        let (tire, _) = shape_renderer.add_oval();
+        shape_renderer
+            .oval_mut(tire)
+            .unwrap()
+            .scale(10.0, 10.0)
+            .color(0.0, 0.0, 1.0)
+            .layer(1);
+        shape_renderer.set_parent(tire, Some(wheel));
+
+        frame(shape_renderer, device, queue);
+        assert_eq!(
+            shape_renderer.shape_at((10.0, 0.0)),
+            Some(ShapeId::Retained(tire))
+        );
+
+        // the cached transform of the wheel has to follow the car, (10,0) in the car -> (-20,40)
+        shape_renderer
+            .group_mut(car)
+            .unwrap()
+            .pos(-40.0, 40.0)
+            .scale(2.0, 2.0)
+            .layer(1);
+
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+        assert_eq!(shape_renderer.shape_at((10.0, 0.0)), None);
+        assert_eq!(
+            shape_renderer.shape_at((-20.0, 40.0)),
+            Some(ShapeId::Retained(tire))
+        );
+    });
+}
+
+#[test]
+fn remove_group() {
+    with_renderer(|shape_renderer, _, _| {
+        let (car, _) = shape_renderer.add_group(None);
+        let (wheel, _) = shape_renderer.add_group(Some(car));
+
+        let (body, _) = shape_renderer.add_rect();
+        let (tire, _) = shape_renderer.add_oval();
+        let (outside, _) = shape_renderer.add_rect();
+        shape_renderer.set_parent(body, Some(car));
+        shape_renderer.set_parent(tire, Some(wheel));
+
+        assert!(shape_renderer.remove_group(car));
+        assert!(!shape_renderer.remove_group(car));
+
+        assert!(!shape_renderer.contains_group(wheel));
+        assert!(!shape_renderer.contains(body));
+        assert!(!shape_renderer.contains(tire));
+        assert!(shape_renderer.contains(outside));
+        assert!(!shape_renderer.set_parent(outside, Some(car)));
+    });
+}
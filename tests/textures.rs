@@ -0,0 +1,434 @@
+use std::io::Cursor;
+
+use image::{ImageOutputFormat, Rgba, RgbaImage};
+use wgpu::{Device, Queue};
+use wgpu_shapes::cpu_renderer::CpuRenderer;
+use wgpu_shapes::error::Error;
+use wgpu_shapes::render::atlas::TextureId;
+use wgpu_shapes::render::offscreen::OffscreenTarget;
+use wgpu_shapes::shape_renderer::ShapeRenderer;
+
+use common::{render, render_cpu, with_renderer, FORMAT, SIZE};
+
+mod common;
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    colored_png(width, height, [255, 0, 0, 255])
+}
+
+fn colored_png(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+    let mut bytes = Cursor::new(vec![]);
+    RgbaImage::from_pixel(width, height, Rgba(color))
+        .write_to(&mut bytes, ImageOutputFormat::Png)
+        .unwrap();
+    bytes.into_inner()
+}
+
+fn frame(shape_renderer: &mut ShapeRenderer, device: &Device, queue: &Queue) -> RgbaImage {
+    let target = OffscreenTarget::new(device, SIZE, FORMAT);
+    shape_renderer.render_to_image(device, queue, &target)
+}
+
+/// the textures next to each other
+fn draw(shape_renderer: &mut ShapeRenderer, textures: &[TextureId]) {
+    for (index, texture) in textures.iter().enumerate() {
+        shape_renderer
+            .image(*texture)
+            .unwrap()
+            .pos(-60.0 + 60.0 * index as f32, 0.0)
+            .scale(40.0, 30.0);
+    }
+}
+
+#[test]
+fn broken_bytes() {
+    with_renderer(|shape_renderer, device, queue| {
+        let result = shape_renderer.add_texture_from_bytes(b"not an image", device, queue);
+        assert!(matches!(result, Err(Error::Decode(_))));
+
+        let texture = shape_renderer
+            .add_texture_from_bytes(&png(4, 4), device, queue)
+            .unwrap();
+        shape_renderer.image(texture).unwrap().scale(20.0, 20.0);
+    });
+}
+
+#[test]
+fn all_or_nothing() {
+    let mut cpu_renderer = CpuRenderer::new();
+    let valid = png(2, 2);
+
+    let result = cpu_renderer.add_textures_from_bytes(&[&valid, b"broken"]);
+    assert!(matches!(result, Err(Error::Decode(_))));
+
+    let textures = cpu_renderer
+        .add_textures_from_bytes(&[&valid, &valid])
+        .unwrap();
+    assert_eq!(textures.len(), 2);
+    assert_ne!(textures[0], textures[1]);
+}
+
+#[test]
+fn too_large() {
+    let mut cpu_renderer = CpuRenderer::new();
+    cpu_renderer.set_max_atlas_size(8);
+
+    let result = cpu_renderer.add_texture_from_bytes(&png(16, 4));
+    assert!(matches!(
+        result,
+        Err(Error::TextureTooLarge {
+            width: 16,
+            height: 4,
+            max_size: 8
+        })
+    ));
+
+    // the rest goes onto more pages
+    let textures = cpu_renderer
+        .add_textures_from_bytes(&[&png(8, 8), &png(8, 8), &png(4, 4)])
+        .unwrap();
+    assert_eq!(textures.len(), 3);
+}
+
+#[test]
+fn texture_of_another_renderer() {
+    let mut first = CpuRenderer::new();
+    let mut second = CpuRenderer::new();
+
+    let texture = first.add_texture_from_bytes(&png(2, 2)).unwrap();
+    second.add_texture_from_bytes(&png(2, 2)).unwrap();
+
+    assert!(matches!(
+        second.image(texture),
+        Err(Error::UnknownTexture(unknown)) if unknown == texture
+    ));
+
+    with_renderer(|shape_renderer, _, _| {
+        assert!(matches!(
+            shape_renderer.image(texture),
+            Err(Error::UnknownTexture(_))
+        ));
+        assert!(matches!(
+            shape_renderer.rounded_image(texture),
+            Err(Error::UnknownTexture(_))
+        ));
+        assert!(matches!(
+            shape_renderer.add_image(texture),
+            Err(Error::UnknownTexture(_))
+        ));
+    });
+}
+
+#[test]
+fn added_one_by_one() {
+    let images = [
+        colored_png(40, 30, [255, 0, 0, 255]),
+        colored_png(40, 30, [0, 255, 0, 255]),
+        colored_png(40, 30, [0, 0, 255, 255]),
+    ];
+
+    let expected = render(|shape_renderer, device, queue| {
+        let bytes: Vec<&[u8]> = images.iter().map(Vec::as_slice).collect();
+        let textures = shape_renderer
+            .add_textures_from_bytes(&bytes, device, queue)
+            .unwrap();
+        draw(shape_renderer, &textures);
+    });
+
+    with_renderer(|shape_renderer, device, queue| {
+        let mut textures = vec![];
+
+        // the textures which are already uploaded stay where they are
+        for image in &images {
+            textures.push(
+                shape_renderer
+                    .add_texture_from_bytes(image, device, queue)
+                    .unwrap(),
+            );
+            shape_renderer.clear();
+            draw(shape_renderer, &textures);
+            frame(shape_renderer, device, queue);
+        }
+
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+    });
+}
+
+#[test]
+fn repacked_when_full() {
+    let small = colored_png(300, 300, [255, 0, 0, 255]);
+    let large = colored_png(500, 500, [0, 0, 255, 255]);
+
+    let expected = render(|shape_renderer, device, queue| {
+        let textures = shape_renderer
+            .add_textures_from_bytes(&[&small, &large], device, queue)
+            .unwrap();
+        draw(shape_renderer, &textures);
+    });
+
+    with_renderer(|shape_renderer, device, queue| {
+        let first = shape_renderer
+            .add_texture_from_bytes(&small, device, queue)
+            .unwrap();
+        shape_renderer
+            .add_image(first)
+            .unwrap()
+            .1
+            .pos(-60.0, 0.0)
+            .scale(40.0, 30.0);
+        frame(shape_renderer, device, queue);
+
+        // does not fit next to the first texture, the retained image has to follow it
+        let second = shape_renderer
+            .add_texture_from_bytes(&large, device, queue)
+            .unwrap();
+        shape_renderer.image(second).unwrap().scale(40.0, 30.0);
+
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+    });
+}
+
+#[test]
+fn padding_keeps_the_image() {
+    let images = [
+        colored_png(7, 5, [255, 0, 0, 255]),
+        colored_png(3, 9, [0, 255, 0, 128]),
+        colored_png(6, 6, [0, 0, 255, 255]),
+    ];
+
+    let scene = |padding: u32| {
+        render_cpu(|cpu_renderer| {
+            cpu_renderer.set_atlas_padding(padding);
+
+            let mut textures = vec![];
+            for image in &images {
+                textures.push(cpu_renderer.add_texture_from_bytes(image).unwrap());
+            }
+
+            for (index, texture) in textures.into_iter().enumerate() {
+                cpu_renderer
+                    .image(texture)
+                    .unwrap()
+                    .pos(-60.0 + 60.0 * index as f32, 0.0)
+                    .scale(40.0, 30.0)
+                    .rotation(0.3);
+            }
+        })
+    };
+
+    assert_eq!(scene(0), scene(4));
+}
+
+/// every pixel has another color
+fn gradient_png(width: u32, height: u32, blue: u8) -> (RgbaImage, Vec<u8>) {
+    let image = RgbaImage::from_fn(width, height, |x, y| {
+        Rgba([x as u8 * 40, y as u8 * 40, blue, 255])
+    });
+
+    let mut bytes = Cursor::new(vec![]);
+    image.write_to(&mut bytes, ImageOutputFormat::Png).unwrap();
+    (image, bytes.into_inner())
+}
+
+#[test]
+fn padding_repeats_the_edges() {
+    let padding = 2;
+    let (first, first_bytes) = gradient_png(4, 3, 100);
+    let (second, second_bytes) = gradient_png(5, 6, 200);
+
+    // exactly wide enough for both next to each other, so the padding gets cut at the page edges
+    let size = first.width() + second.width() + 2 * padding;
+
+    let mut cpu_renderer = CpuRenderer::new();
+    cpu_renderer
+        .set_max_atlas_size(size)
+        .set_atlas_padding(padding);
+    cpu_renderer
+        .add_textures_from_bytes(&[&first_bytes, &second_bytes])
+        .unwrap();
+
+    let pages = cpu_renderer.atlas_pages();
+    assert_eq!(pages.len(), 1);
+    let page = &pages[0];
+    assert_eq!(page.dimensions(), (size, size));
+
+    for texture in [&first, &second] {
+        let (width, height) = texture.dimensions();
+
+        let (x, y) = (0..=size - width)
+            .flat_map(|x| (0..=size - height).map(move |y| (x, y)))
+            .find(|&(x, y)| {
+                (0..width).all(|tx| {
+                    (0..height)
+                        .all(|ty| page.get_pixel(x + tx, y + ty) == texture.get_pixel(tx, ty))
+                })
+            })
+            .expect("the texture is on the page");
+
+        // the padding inside of the page repeats the closest edge pixel
+        let left = x.saturating_sub(padding);
+        let top = y.saturating_sub(padding);
+        for page_x in left..(x + width + padding).min(size) {
+            for page_y in top..(y + height + padding).min(size) {
+                let edge = texture.get_pixel(
+                    page_x.clamp(x, x + width - 1) - x,
+                    page_y.clamp(y, y + height - 1) - y,
+                );
+                assert_eq!(page.get_pixel(page_x, page_y), edge, "({page_x},{page_y})");
+            }
+        }
+    }
+}
+
+#[test]
+fn padding_at_the_edge() {
+    let mut cpu_renderer = CpuRenderer::new();
+    cpu_renderer.set_max_atlas_size(8).set_atlas_padding(2);
+
+    // the padding does not have to fit outside of the page
+    let textures = cpu_renderer
+        .add_textures_from_bytes(&[&png(8, 8), &png(8, 3)])
+        .unwrap();
+    assert_eq!(textures.len(), 2);
+}
+
+#[test]
+fn immediate_images_follow_repack() {
+    let small = colored_png(300, 300, [255, 0, 0, 255]);
+    let large = colored_png(500, 500, [0, 0, 255, 255]);
+    let removed = colored_png(20, 20, [0, 255, 0, 255]);
+
+    let expected = render(|shape_renderer, device, queue| {
+        let textures = shape_renderer
+            .add_textures_from_bytes(&[&small, &large], device, queue)
+            .unwrap();
+        draw(shape_renderer, &textures);
+    });
+
+    with_renderer(|shape_renderer, device, queue| {
+        let textures = shape_renderer
+            .add_textures_from_bytes(&[&small, &removed], device, queue)
+            .unwrap();
+        draw(shape_renderer, &textures[..1]);
+        shape_renderer
+            .image(textures[1])
+            .unwrap()
+            .pos(60.0, 0.0)
+            .scale(40.0, 30.0);
+
+        // the images created before look up where their texture is now
+        let large = shape_renderer
+            .add_texture_from_bytes(&large, device, queue)
+            .unwrap();
+        shape_renderer.image(large).unwrap().scale(40.0, 30.0);
+        shape_renderer.remove_texture(textures[1]);
+
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+    });
+}
+
+#[test]
+fn remove_and_compact() {
+    let red = colored_png(300, 300, [255, 0, 0, 255]);
+    let green = colored_png(200, 400, [0, 255, 0, 255]);
+    let blue = colored_png(40, 30, [0, 0, 255, 255]);
+
+    let expected = render(|shape_renderer, device, queue| {
+        let textures = shape_renderer
+            .add_textures_from_bytes(&[&red, &blue], device, queue)
+            .unwrap();
+        shape_renderer
+            .image(textures[0])
+            .unwrap()
+            .pos(-60.0, 0.0)
+            .scale(40.0, 30.0);
+        shape_renderer
+            .image(textures[1])
+            .unwrap()
+            .pos(60.0, 0.0)
+            .scale(40.0, 30.0);
+    });
+
+    with_renderer(|shape_renderer, device, queue| {
+        let textures = shape_renderer
+            .add_textures_from_bytes(&[&red, &green, &blue], device, queue)
+            .unwrap();
+        draw(shape_renderer, &textures);
+        frame(shape_renderer, device, queue);
+        shape_renderer.clear();
+
+        // retained images of the removed texture are not drawn anymore
+        for (index, texture) in textures.iter().enumerate() {
+            shape_renderer
+                .add_image(*texture)
+                .unwrap()
+                .1
+                .pos(-60.0 + 60.0 * index as f32, 0.0)
+                .scale(40.0, 30.0);
+        }
+        assert!(shape_renderer.remove_texture(textures[1]));
+        assert!(!shape_renderer.remove_texture(textures[1]));
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+
+        // the other textures move but their ids stay valid
+        shape_renderer.compact_textures(device, queue).unwrap();
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+    });
+}
+
+#[test]
+fn dropped_copies() {
+    let small = colored_png(300, 300, [255, 0, 0, 255]);
+    let large = colored_png(500, 500, [0, 0, 255, 255]);
+    let removed = colored_png(100, 100, [0, 255, 0, 255]);
+
+    let expected = render(|shape_renderer, device, queue| {
+        let textures = shape_renderer
+            .add_textures_from_bytes(&[&small, &large], device, queue)
+            .unwrap();
+        draw(shape_renderer, &textures);
+    });
+
+    with_renderer(|shape_renderer, device, queue| {
+        shape_renderer.set_keep_texture_copies(false);
+
+        let mut textures = vec![
+            shape_renderer
+                .add_texture_from_bytes(&removed, device, queue)
+                .unwrap(),
+            shape_renderer
+                .add_texture_from_bytes(&small, device, queue)
+                .unwrap(),
+        ];
+        shape_renderer.remove_texture(textures.remove(0));
+
+        // everything gets packed again, the first texture only exists on the gpu
+        textures.push(
+            shape_renderer
+                .add_texture_from_bytes(&large, device, queue)
+                .unwrap(),
+        );
+        draw(shape_renderer, &textures);
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+
+        shape_renderer.clear();
+        shape_renderer.set_atlas_padding(3);
+        shape_renderer.compact_textures(device, queue).unwrap();
+        draw(shape_renderer, &textures);
+        assert_eq!(frame(shape_renderer, device, queue), expected);
+    });
+}
+
+#[test]
+fn removed_texture() {
+    let mut cpu_renderer = CpuRenderer::new();
+
+    let texture = cpu_renderer.add_texture_from_bytes(&png(2, 2)).unwrap();
+    assert!(cpu_renderer.remove_texture(texture));
+    cpu_renderer.compact_textures().unwrap();
+
+    assert!(matches!(
+        cpu_renderer.image(texture),
+        Err(Error::UnknownTexture(_))
+    ));
+}